@@ -0,0 +1,30 @@
+use hostlink::{device::PlcDevice, protocol::NodeId};
+use serialport::{DataBits, FlowControl, StopBits};
+use std::time::Duration;
+
+fn main() {
+    let mut device = PlcDevice::connect_with_builder(
+        serialport::new("/dev/cu.usbserial-14130", 9600)
+            .data_bits(DataBits::Seven)
+            .flow_control(FlowControl::None)
+            .parity(serialport::Parity::Even)
+            .stop_bits(StopBits::Two),
+        NodeId::new(0).unwrap(),
+        None,
+    )
+    .unwrap();
+
+    println!("Connected");
+    let level = device.subscribe("DM100".parse().unwrap(), 5);
+    let alarm = device.subscribe("IR001.03".parse().unwrap(), 0);
+
+    loop {
+        device.poll_subscriptions();
+
+        for change in level.try_iter().chain(alarm.try_iter()) {
+            println!("{change:?}");
+        }
+
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
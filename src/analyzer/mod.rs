@@ -0,0 +1,324 @@
+use crate::protocol::{
+    responses::words::Words, DeviceError, FrameDecoder, FrameError, Message, MessageKind, NodeId,
+    ProtocolError, Response, ResponseData,
+};
+use derive_more::Display;
+use std::time::{Duration, SystemTime};
+
+/// Default inter-character gap after which an unterminated frame is considered incomplete.
+pub const DEFAULT_GAP: Duration = Duration::from_millis(100);
+
+/// Default response time above which a response is flagged as slow.
+pub const DEFAULT_LATENCY_LIMIT: Duration = Duration::from_secs(1);
+
+/// Who sent a frame.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    /// Sent by the host (e.g. a SCADA system).
+    #[display(fmt = "HOST")]
+    Command,
+    /// Sent by a PLC.
+    #[display(fmt = "PLC")]
+    Response,
+    /// The frame couldn't be parsed, so its sender is unknown.
+    #[display(fmt = "?")]
+    Unknown,
+}
+
+/// A problem noticed on the line.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Issue {
+    /// The frame's FCS doesn't match its contents.
+    #[display(fmt = "FCS mismatch")]
+    FcsMismatch,
+    /// The frame couldn't be parsed.
+    #[display(fmt = "malformed frame")]
+    Malformed,
+    /// The line went quiet (or a new frame started) before the terminator was received.
+    #[display(fmt = "incomplete frame")]
+    Incomplete,
+    /// The response took longer than the latency limit.
+    #[display(fmt = "slow response ({:?})", _0)]
+    SlowResponse(Duration),
+    /// The previous command was never answered.
+    #[display(fmt = "previous command unanswered")]
+    MissingResponse,
+}
+
+/// A frame seen on the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// When the first byte of the frame was received.
+    pub timestamp: SystemTime,
+    /// Time since the end of the previous frame.
+    pub gap: Option<Duration>,
+    /// The raw frame, including the terminator.
+    pub raw: String,
+    pub direction: Direction,
+    pub message: Result<Message, ProtocolError>,
+    pub issues: Vec<Issue>,
+}
+
+impl Frame {
+    /// Returns a human-readable description of the frame's contents.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match (&self.message, self.direction) {
+            (Ok(message), Direction::Response) => Response::try_from(message.clone()).map_or_else(
+                |error| error.to_string(),
+                |response| describe_response(&response),
+            ),
+            (Ok(message), _) => {
+                let params = String::from_utf8_lossy(message.params());
+                format!("{} {params}", message.kind()).trim_end().into()
+            }
+            (Err(error), _) => error.to_string(),
+        }
+    }
+}
+
+/// Splits raw bytes read from a tap on a Host Link line into frames and decodes them.
+///
+/// Frames are split by a [`FrameDecoder`], like [`PlcDevice`](crate::device::PlcDevice) does, so
+/// frames longer than its maximum length are dropped instead of being buffered without limit.
+///
+/// Commands and responses look alike, so a frame is considered a response if it has the same node
+/// and header code as the last unanswered command, or is an
+/// [`UndefinedCommand`](MessageKind::UndefinedCommand) frame from that node.
+/// # Example
+/// ```rust
+/// use hostlink::analyzer::{Analyzer, Direction};
+/// use std::time::SystemTime;
+///
+/// let mut analyzer = Analyzer::new();
+/// let frames = analyzer.feed(b"@00MS5E*\r@00MS00002854*\r", SystemTime::now());
+///
+/// assert_eq!(frames[0].direction, Direction::Command);
+/// assert_eq!(frames[1].direction, Direction::Response);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analyzer {
+    gap: Duration,
+    latency_limit: Duration,
+    decoder: FrameDecoder,
+    /// When the first byte of the pending frame was received
+    started: Option<SystemTime>,
+    last_byte: Option<SystemTime>,
+    last_frame: Option<SystemTime>,
+    pending: Option<Pending>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pending {
+    node: NodeId,
+    kind: MessageKind,
+    raw: String,
+    sent: SystemTime,
+}
+
+impl Analyzer {
+    /// Creates an analyzer with the default gap and latency limit.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            gap: DEFAULT_GAP,
+            latency_limit: DEFAULT_LATENCY_LIMIT,
+            decoder: FrameDecoder::new(),
+            started: None,
+            last_byte: None,
+            last_frame: None,
+            pending: None,
+        }
+    }
+
+    /// Sets the inter-character gap after which an unterminated frame is considered incomplete.
+    #[must_use]
+    pub const fn with_gap(mut self, gap: Duration) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Sets the response time above which a response is flagged as slow.
+    #[must_use]
+    pub const fn with_latency_limit(mut self, latency_limit: Duration) -> Self {
+        self.latency_limit = latency_limit;
+        self
+    }
+
+    /// Processes bytes received at `timestamp`, returning the frames they completed.
+    pub fn feed(&mut self, bytes: &[u8], timestamp: SystemTime) -> Vec<Frame> {
+        let mut frames: Vec<Frame> = self.idle(timestamp).into_iter().collect();
+
+        if bytes.is_empty() {
+            return frames;
+        }
+
+        self.last_byte = Some(timestamp);
+        let mut bytes = bytes;
+
+        while !bytes.is_empty() {
+            let accepted = self.decoder.push(bytes);
+            bytes = &bytes[accepted..];
+
+            loop {
+                // bytes the decoder drops are still shown, as far as they were received
+                let pending = self.decoder.pending().to_vec();
+                let (raw, terminated) = match self.decoder.next_frame() {
+                    Some(Ok(frame)) => (frame.to_vec(), true),
+                    Some(Err(error)) => {
+                        let dropped = pending.len() - self.decoder.pending().len();
+                        let terminated = !matches!(error, FrameError::Unterminated(..));
+                        (pending[..dropped].to_vec(), terminated)
+                    }
+                    None => break,
+                };
+
+                let started = self.started.take().unwrap_or(timestamp);
+                frames.push(self.flush(&raw, started, terminated));
+            }
+
+            if !self.decoder.pending().is_empty() {
+                self.started.get_or_insert(timestamp);
+            }
+        }
+
+        frames
+    }
+
+    /// Flushes an unterminated frame if the line has been quiet for longer than the gap.
+    /// Should be called periodically while no bytes are received.
+    pub fn idle(&mut self, now: SystemTime) -> Option<Frame> {
+        let quiet = now.duration_since(self.last_byte?).unwrap_or_default();
+
+        if self.decoder.pending().is_empty() || quiet <= self.gap {
+            return None;
+        }
+
+        let raw = self.decoder.pending().to_vec();
+        self.decoder.clear();
+        let started = self.started.take().unwrap_or(SystemTime::UNIX_EPOCH);
+
+        Some(self.flush(&raw, started, false))
+    }
+
+    fn flush(&mut self, raw: &[u8], timestamp: SystemTime, terminated: bool) -> Frame {
+        let raw = String::from_utf8_lossy(raw).into_owned();
+        let gap = self
+            .last_frame
+            .map(|last| timestamp.duration_since(last).unwrap_or_default());
+        self.last_frame = self.last_byte;
+
+        let message = Message::parse(&raw);
+        let mut issues = Vec::new();
+
+        if !terminated {
+            issues.push(Issue::Incomplete);
+        }
+
+        match &message {
+            Err(ProtocolError::FcsMismatch { .. }) => issues.push(Issue::FcsMismatch),
+            Err(_) if terminated => issues.push(Issue::Malformed),
+            _ => (),
+        }
+
+        let direction = match &message {
+            Ok(message) => self.classify(message, &raw, timestamp, &mut issues),
+            Err(_) => Direction::Unknown,
+        };
+
+        Frame {
+            timestamp,
+            gap,
+            raw,
+            direction,
+            message,
+            issues,
+        }
+    }
+
+    fn classify(
+        &mut self,
+        message: &Message,
+        raw: &str,
+        timestamp: SystemTime,
+        issues: &mut Vec<Issue>,
+    ) -> Direction {
+        match self.pending.take() {
+            // A repeated command is a retry, not an echo (except for TEST, whose response is an echo)
+            Some(pending)
+                if pending.node == message.node()
+                    && (pending.kind == message.kind()
+                        || message.kind() == MessageKind::UndefinedCommand)
+                    && (pending.raw != raw || message.kind() == MessageKind::Test) =>
+            {
+                let latency = timestamp.duration_since(pending.sent).unwrap_or_default();
+
+                if latency > self.latency_limit {
+                    issues.push(Issue::SlowResponse(latency));
+                }
+
+                Direction::Response
+            }
+            // never answered, and cancels whatever was pending
+            _ if !message.kind().has_response() => Direction::Command,
+            pending => {
+                if pending.is_some() {
+                    issues.push(Issue::MissingResponse);
+                }
+
+                self.pending = Some(Pending {
+                    node: message.node(),
+                    kind: message.kind(),
+                    raw: raw.into(),
+                    sent: timestamp,
+                });
+
+                Direction::Command
+            }
+        }
+    }
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decodes a response using the parser matching its header code.
+#[must_use]
+pub fn describe_response(response: &Response) -> String {
+    // a parsed IC frame doesn't say which command was rejected
+    if let Some(DeviceError::UnsupportedCommand(MessageKind::UndefinedCommand)) = response.error() {
+        return "undefined command".into();
+    }
+
+    if let Some(error) = response.error() {
+        return format!("end code: {error}");
+    }
+
+    match response.decode() {
+        Ok(ResponseData::Echo(data)) => format!("echo \"{data}\""),
+        Ok(ResponseData::Status(status)) => format!("{status:?}"),
+        Ok(ResponseData::Model(model)) => model.to_string(),
+        Ok(ResponseData::Words(Words(words))) => words
+            .iter()
+            .map(|word| format!("{word:04X}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+        Ok(ResponseData::Flags(flags)) => flags
+            .iter()
+            .map(|&flag| if flag { '1' } else { '0' })
+            .collect(),
+        Ok(ResponseData::Fins(fins)) => format!(
+            "FINS {:04X} SID {:02X}: {}, {} data bytes",
+            fins.command,
+            fins.header.sid,
+            fins.end_code.description(),
+            fins.data.len()
+        ),
+        Ok(ResponseData::Raw(data)) => data,
+        Ok(ResponseData::Empty) => format!("end code: {}", response.end_code()),
+        Err(error) => format!("undecodable: {error}"),
+    }
+}
@@ -0,0 +1,114 @@
+use clap::Parser;
+use hostlink::cli::{load_tags, Poller, SerialArgs};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    process::ExitCode,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Polls PLCs over Omron Hostlink and serves tag values and link health as Prometheus metrics.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    #[command(flatten)]
+    serial: SerialArgs,
+
+    /// Tag list, one `name node address` entry per line (e.g. `tank_level 0 DM0100`)
+    tags: PathBuf,
+
+    /// Address to serve `/metrics` on
+    #[arg(short, long, default_value = "127.0.0.1:9853")]
+    listen: SocketAddr,
+
+    /// Polling interval in milliseconds
+    #[arg(short, long, default_value_t = 1000)]
+    interval: u64,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<(), String> {
+    let tags = load_tags(&cli.tags)?;
+    let first = tags.first().ok_or("the tag list is empty")?.node;
+    let mut device = cli
+        .serial
+        .connect(first)
+        .map_err(|error| error.to_string())?;
+    let listener =
+        TcpListener::bind(cli.listen).map_err(|error| format!("{}: {error}", cli.listen))?;
+
+    let metrics = Arc::new(Mutex::new(String::new()));
+    let interval = Duration::from_millis(cli.interval);
+
+    {
+        let metrics = metrics.clone();
+        std::thread::spawn(move || {
+            let mut poller = Poller::new(tags);
+
+            loop {
+                let text = poller.poll(&mut device, |node, error| {
+                    eprintln!("node {node}: {error}");
+                });
+                *metrics
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner) = text;
+                std::thread::sleep(interval);
+            }
+        });
+    }
+
+    eprintln!("serving metrics on http://{}/metrics", cli.listen);
+
+    for stream in listener.incoming().flatten() {
+        if let Err(error) = respond(stream, &metrics) {
+            eprintln!("error: {error}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Answers a single HTTP request.
+fn respond(stream: TcpStream, metrics: &Mutex<String>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(&stream);
+
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+
+    // skip the headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let (status, body) = match request.split_whitespace().nth(1) {
+        Some("/metrics") => (
+            "200 OK",
+            metrics
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .clone(),
+        ),
+        _ => ("404 Not Found", "Not found\n".to_string()),
+    };
+
+    write!(
+        &stream,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
@@ -0,0 +1,371 @@
+mod output;
+mod repl;
+mod sniff;
+
+use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use hostlink::{
+    cli::{parse_node, SerialArgs},
+    device::{DeviceError, Error, PlcDevice},
+    modbus::{Map, ModbusGateway, Range, Table},
+    protocol::{responses::status::StatusMode, Address, Area, NodeId, MAX_READ_WORDS},
+};
+use output::Format;
+use serde_json::{json, Value};
+use std::{
+    fs::File,
+    io::Write,
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    process::ExitCode,
+    time::Duration,
+};
+
+/// Field diagnostics for PLCs connected over Omron Hostlink.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    #[command(flatten)]
+    link: LinkArgs,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Table, global = true)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Link settings.
+#[derive(Debug, Args)]
+struct LinkArgs {
+    #[command(flatten)]
+    serial: SerialArgs,
+
+    /// Node ID of the PLC
+    #[arg(short, long, global = true, default_value = "0", value_parser = parse_node)]
+    node: NodeId,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Mode {
+    Program,
+    Monitor,
+    Run,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Checks the link using a TEST command
+    Test,
+    /// Reads the PLC's operating status
+    Status,
+    /// Reads the PLC's model
+    Model,
+    /// Reads words or a bit (e.g. `read DM100 10` or `read IR001.03`)
+    Read {
+        address: Address,
+        /// Number of words
+        #[arg(default_value_t = 1)]
+        count: u16,
+    },
+    /// Writes words (e.g. `write HR5 0x1234`); use `force set/reset` for single bits
+    Write {
+        address: Address,
+        /// Values to write (decimal or hexadecimal with a `0x` prefix)
+        #[arg(required = true, value_parser = parse_word)]
+        values: Vec<u16>,
+    },
+    /// Reads the PLC's error information
+    Errors {
+        /// Clear errors after reading them
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Changes the operation mode
+    Mode { mode: Mode },
+    /// Forces bits on or off
+    Force {
+        #[command(subcommand)]
+        action: ForceAction,
+    },
+    /// Saves the contents of all data areas that the PLC has into a file
+    Backup { file: PathBuf },
+    /// Starts an interactive shell for sending raw or symbolic commands
+    Repl,
+    /// Serves Modbus TCP requests by translating them into Hostlink commands
+    Gateway {
+        /// Address to listen on
+        #[arg(short, long, default_value = "127.0.0.1:502")]
+        listen: SocketAddr,
+        /// Holding registers, as `START=ADDRESS:COUNT` (e.g. `0=DM0000:1000`)
+        #[arg(long, value_parser = parse_range)]
+        holding: Vec<Range>,
+        /// Input registers, as `START=ADDRESS:COUNT`
+        #[arg(long, value_parser = parse_range)]
+        input: Vec<Range>,
+        /// Coils, as `START=ADDRESS:COUNT` (e.g. `0=IR000.00:256`)
+        #[arg(long, value_parser = parse_range)]
+        coils: Vec<Range>,
+        /// Discrete inputs, as `START=ADDRESS:COUNT`
+        #[arg(long, value_parser = parse_range)]
+        discrete: Vec<Range>,
+    },
+    /// Passively decodes traffic on a tap port, without sending anything
+    Sniff {
+        /// Inter-character gap (in milliseconds) after which an unterminated frame is reported
+        #[arg(long, default_value_t = 100)]
+        gap: u64,
+        /// Response time (in milliseconds) above which a response is flagged as slow
+        #[arg(long, default_value_t = 1000)]
+        latency: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ForceAction {
+    /// Force-sets a bit (e.g. `force set IR001.03`)
+    Set { address: Address },
+    /// Force-resets a bit
+    Reset { address: Address },
+    /// Cancels all forced bits
+    Cancel,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // writing a word back would overwrite any of its other bits the PLC changed in between
+    if let Command::Write {
+        address: Address { bit: Some(_), .. },
+        ..
+    } = &cli.command
+    {
+        Cli::command()
+            .error(
+                ErrorKind::ValueValidation,
+                "can't write a single bit, use `force set` or `force reset` instead",
+            )
+            .exit();
+    }
+
+    let interactive = match cli.command {
+        Command::Repl => Some(connect(&cli.link).and_then(|mut device| repl::run(&mut device))),
+        Command::Sniff { gap, latency } => Some(sniff::run(
+            cli.link.serial.builder(),
+            cli.format,
+            Duration::from_millis(gap),
+            Duration::from_millis(latency),
+        )),
+        Command::Gateway {
+            listen,
+            ref holding,
+            ref input,
+            ref coils,
+            ref discrete,
+        } => Some(connect(&cli.link).and_then(|device| {
+            let tables = [
+                (Table::HoldingRegisters, holding),
+                (Table::InputRegisters, input),
+                (Table::Coils, coils),
+                (Table::DiscreteInputs, discrete),
+            ];
+            let map = tables.into_iter().fold(Map::new(), |map, (table, ranges)| {
+                ranges.iter().fold(map, |map, range| {
+                    map.with(table, range.start, range.count, range.target)
+                })
+            });
+
+            eprintln!("serving Modbus TCP on {listen}");
+            ModbusGateway::new(device, map).serve(TcpListener::bind(listen)?)?;
+
+            Ok(())
+        })),
+        _ => None,
+    };
+
+    if let Some(result) = interactive {
+        return match result {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("error: {error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    match connect(&cli.link).and_then(|mut device| run(&mut device, cli.command)) {
+        Ok(report) => {
+            output::print(cli.format, &report);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn connect(link: &LinkArgs) -> Result<PlcDevice, Error> {
+    link.serial.connect(link.node)
+}
+
+fn run(device: &mut PlcDevice, command: Command) -> Result<Value, Error> {
+    match command {
+        Command::Test => {
+            device.test()?;
+            Ok(json!({ "test": "OK" }))
+        }
+        Command::Status => {
+            let status = device.status()?;
+
+            Ok(json!({
+                "mode": status.mode.to_string(),
+                "fals": status.fals,
+                "error": status.error,
+                "program_size": status.memory.size,
+                "write_protection": status.memory.write_protection,
+            }))
+        }
+        Command::Model => {
+            let model = device.model()?;
+
+            Ok(json!({ "code": format!("{:02X}", model.0), "model": model.name() }))
+        }
+        Command::Read { address, count } => {
+            if let Some(bit) = address.bit {
+                let word = device.read_words(address.area, address.word, 1)?[0];
+                let value = word & (1 << bit) != 0;
+
+                return Ok(json!([{ "address": address.to_string(), "value": u8::from(value) }]));
+            }
+
+            let words = device.read_words(address.area, address.word, count)?;
+
+            Ok(words
+                .iter()
+                .zip(address.word..)
+                .map(|(value, word)| {
+                    json!({
+                        "address": Address::word(address.area, word).to_string(),
+                        "hex": format!("{value:04X}"),
+                        "decimal": value,
+                    })
+                })
+                .collect())
+        }
+        Command::Write { address, values } => {
+            device.write_words(address.area, address.word, &values)?;
+
+            Ok(json!({ "address": address.to_string(), "written": values.len() }))
+        }
+        Command::Errors { clear } => {
+            let errors = device.errors(clear)?;
+
+            Ok(json!({
+                "errors": errors.iter().map(|word| format!("{word:04X}")).collect::<Vec<_>>(),
+                "cleared": clear,
+            }))
+        }
+        Command::Mode { mode } => {
+            let mode = match mode {
+                Mode::Program => StatusMode::Program,
+                Mode::Monitor => StatusMode::Monitor,
+                Mode::Run => StatusMode::Run,
+            };
+            device.set_mode(mode)?;
+
+            Ok(json!({ "mode": mode.to_string() }))
+        }
+        Command::Force { action } => {
+            match action {
+                ForceAction::Set { address } => device.force(address, true)?,
+                ForceAction::Reset { address } => device.force(address, false)?,
+                ForceAction::Cancel => device.cancel_forced()?,
+            }
+
+            Ok(json!({ "force": "OK" }))
+        }
+        Command::Backup { file } => backup(device, &file),
+        Command::Repl | Command::Sniff { .. } | Command::Gateway { .. } => {
+            unreachable!("interactive commands run from main")
+        }
+    }
+}
+
+/// Saves all data areas, 8 words per line (e.g. `DM0000 0000 0001 ...`).
+///
+/// Areas which the PLC doesn't have are skipped, and an area is cut short at the first
+/// address beyond its end on this model.
+fn backup(device: &mut PlcDevice, path: &PathBuf) -> Result<Value, Error> {
+    let capabilities = device.probe_capabilities()?.clone();
+    let mut file = File::create(path)?;
+    let mut total = 0;
+    let mut skipped = Vec::new();
+
+    for area in Area::ALL {
+        if !capabilities.supports_area(area) {
+            skipped.push(area.to_string());
+            continue;
+        }
+
+        let words = read_area(device, area)?;
+
+        for (line, chunk) in (0..).step_by(8).zip(words.chunks(8)) {
+            write!(file, "{}", Address::word(area, line))?;
+
+            for word in chunk {
+                write!(file, " {word:04X}")?;
+            }
+
+            writeln!(file)?;
+        }
+
+        total += words.len();
+    }
+
+    Ok(json!({ "file": path.display().to_string(), "words": total, "skipped": skipped }))
+}
+
+/// Reads an area up to its size, or up to the first address the PLC reports as out of range.
+fn read_area(device: &mut PlcDevice, area: Area) -> Result<Vec<u16>, Error> {
+    let mut words = Vec::with_capacity(area.size().into());
+
+    while words.len() < area.size().into() {
+        #[allow(clippy::cast_possible_truncation)]
+        let start = words.len() as u16;
+        let count = (area.size() - start).min(MAX_READ_WORDS);
+
+        match device.read_words(area, start, count) {
+            Ok(chunk) => words.extend(chunk),
+            Err(Error::Device(DeviceError::AddressOver)) => break,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(words)
+}
+
+fn parse_word(value: &str) -> Result<u16, String> {
+    let result = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+
+    result.map_err(|error| error.to_string())
+}
+
+/// Parses a Modbus range (e.g. `0=DM0000:1000`).
+fn parse_range(value: &str) -> Result<Range, String> {
+    let invalid = || format!("expected START=ADDRESS:COUNT, got '{value}'");
+    let (start, rest) = value.split_once('=').ok_or_else(invalid)?;
+    let (target, count) = rest.rsplit_once(':').ok_or_else(invalid)?;
+
+    Ok(Range {
+        start: start.parse().map_err(|error| format!("{error}"))?,
+        count: count.parse().map_err(|error| format!("{error}"))?,
+        target: target.parse().map_err(|error| format!("{error}"))?,
+    })
+}
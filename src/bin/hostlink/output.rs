@@ -0,0 +1,85 @@
+use clap::ValueEnum;
+use serde_json::Value;
+
+/// How results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human-readable table
+    Table,
+    /// JSON (one document per command)
+    Json,
+}
+
+/// Prints a command's result.
+/// Objects are printed as `key: value` lines, arrays of objects as tables with a header row.
+pub fn print(format: Format, report: &Value) {
+    match format {
+        Format::Json => println!("{report}"),
+        Format::Table => print!("{}", table(report)),
+    }
+}
+
+fn table(report: &Value) -> String {
+    match report {
+        Value::Object(fields) => {
+            let width = fields.keys().map(String::len).max().unwrap_or_default();
+
+            fields
+                .iter()
+                .map(|(key, value)| format!("{key:<width$}  {}\n", cell(value)))
+                .collect()
+        }
+        Value::Array(rows) => {
+            let Some(Value::Object(first)) = rows.first() else {
+                return rows.iter().map(|row| format!("{}\n", cell(row))).collect();
+            };
+
+            let columns: Vec<&String> = first.keys().collect();
+            let cells: Vec<Vec<String>> = rows
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .map(|column| cell(&row[column.as_str()]))
+                        .collect()
+                })
+                .collect();
+            let widths: Vec<usize> = columns
+                .iter()
+                .enumerate()
+                .map(|(index, column)| {
+                    cells
+                        .iter()
+                        .map(|row| row[index].len())
+                        .chain([column.len()])
+                        .max()
+                        .unwrap_or_default()
+                })
+                .collect();
+
+            let header = columns.iter().map(|column| column.to_uppercase());
+            std::iter::once(header.collect::<Vec<_>>())
+                .chain(cells)
+                .map(|row| {
+                    let line: Vec<String> = row
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cell, width)| format!("{cell:<width$}"))
+                        .collect();
+
+                    format!("{}\n", line.join("  ").trim_end())
+                })
+                .collect()
+        }
+        other => format!("{}\n", cell(other)),
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".into(),
+        Value::String(string) => string.clone(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(" "),
+        other => other.to_string(),
+    }
+}
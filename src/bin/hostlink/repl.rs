@@ -0,0 +1,358 @@
+use hostlink::{
+    analyzer::describe_response,
+    device::{Error, PlcDevice},
+    protocol::{
+        responses::status::StatusMode, Address, EasyCommand, MessageKind, MessageParams,
+        MessageRef, NodeId, ProtocolError, Request,
+    },
+};
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, Helper,
+};
+use std::{
+    io::BufRead,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Shortest interval for `watch`, so that it doesn't flood the link.
+const MIN_WATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+const SYMBOLIC: [&str; 12] = [
+    "help", "quit", "test", "status", "model", "read", "write", "errors", "mode", "force", "watch",
+    "exit",
+];
+
+const HELP: &str = "\
+Symbolic commands:
+  test | status | model | errors [clear]
+  read ADDRESS [COUNT]          e.g. read DM100 4
+  write ADDRESS VALUE...        e.g. write HR5 0x1234
+  mode program|monitor|run
+  force set|reset ADDRESS       e.g. force set IR001.03
+  force cancel
+  watch ADDRESS [INTERVAL]      e.g. watch DM100 1s (press Enter to stop)
+Raw commands (header code followed by parameters, spaces are ignored):
+  RD 0100 0004
+  MS
+Other: help, quit";
+
+/// Errors of a single line entered into the shell.
+#[derive(Debug, thiserror::Error)]
+enum ReplError {
+    #[error("invalid command `{0}`, type `help` for usage")]
+    Usage(String),
+
+    #[error("invalid interval `{0}`, expected e.g. 500ms, 1s or 2m (at least 100ms)")]
+    Interval(String),
+
+    #[error(transparent)]
+    Device(#[from] Error),
+}
+
+impl From<ProtocolError> for ReplError {
+    fn from(error: ProtocolError) -> Self {
+        Self::Device(error.into())
+    }
+}
+
+/// Completes header codes and symbolic command names.
+struct CommandHelper;
+
+impl Completer for CommandHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let word = &line[..pos];
+
+        if word.contains(' ') {
+            return Ok((pos, Vec::new()));
+        }
+
+        let codes = MessageKind::ALL.into_iter().map(|kind| Pair {
+            display: format!("{} ({kind})", kind.code()),
+            replacement: kind.code().into(),
+        });
+        let symbolic = SYMBOLIC.into_iter().map(|name| Pair {
+            display: name.into(),
+            replacement: name.into(),
+        });
+
+        let candidates = codes
+            .chain(symbolic)
+            .filter(|pair| {
+                pair.replacement.starts_with(word)
+                    || pair.replacement.starts_with(&word.to_ascii_uppercase())
+            })
+            .collect();
+
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for CommandHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CommandHelper {}
+
+impl Validator for CommandHelper {}
+
+impl Helper for CommandHelper {}
+
+/// Runs the interactive shell until the user quits.
+pub fn run(device: &mut PlcDevice) -> Result<(), Error> {
+    let mut editor: Editor<CommandHelper, DefaultHistory> =
+        Editor::new().map_err(|error| Error::Io(std::io::Error::other(error)))?;
+    editor.set_helper(Some(CommandHelper));
+
+    let history = history_file();
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    println!(
+        "Connected to node {}. Type `help` for a list of commands.",
+        device.node_id()
+    );
+
+    loop {
+        let line = match editor.readline("hostlink> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(error) => {
+                eprintln!("error: {error}");
+                break;
+            }
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let _ = editor.add_history_entry(line);
+
+        match execute(device, line) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(error) => eprintln!("error: {error}"),
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+
+    Ok(())
+}
+
+/// Executes a single line. Returns `false` if the shell should quit.
+fn execute(device: &mut PlcDevice, line: &str) -> Result<bool, ReplError> {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or_default();
+    let args: Vec<&str> = words.collect();
+
+    match command.to_ascii_lowercase().as_str() {
+        "quit" | "exit" => return Ok(false),
+        "help" => println!("{HELP}"),
+        "watch" => watch(device, &args)?,
+        _ => {
+            let request = to_request(device.node_id(), command, &args)?;
+            exchange(device, request)?;
+        }
+    }
+
+    Ok(true)
+}
+
+/// Sends a command and shows the frames and the decoded response side by side.
+fn exchange(device: &mut PlcDevice, request: Request) -> Result<(), Error> {
+    print_frame("command", &request.clone().serialize()?);
+
+    // ABORT and INITIALIZE are never answered
+    match request.kind() {
+        MessageKind::Abort => return device.abort(),
+        MessageKind::Initialize => return device.initialize(),
+        _ => (),
+    }
+
+    let response = device.send_command(request)?;
+    print_frame("response", &response.clone().serialize()?);
+    println!("{:<10}{}", "decoded", describe_response(&response));
+
+    Ok(())
+}
+
+fn print_frame(label: &str, frame: &str) {
+    let escaped = frame.escape_debug().to_string();
+
+    match frame_fcs(frame) {
+        Some(fcs) => println!("{label:<10}{escaped:<40}FCS {fcs}"),
+        None => println!("{label:<10}{escaped}"),
+    }
+}
+
+/// Reads an address periodically until Enter is pressed.
+fn watch(device: &mut PlcDevice, args: &[&str]) -> Result<(), ReplError> {
+    let address = Address::from_str(args.first().copied().unwrap_or_default())?;
+    let interval = args.get(1).map_or(Ok(Duration::from_secs(1)), |interval| {
+        parse_interval(interval)
+    })?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopper = {
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            let _ = std::io::stdin().lock().read_line(&mut String::new());
+            stop.store(true, Ordering::Relaxed);
+        })
+    };
+
+    println!("Watching {address} every {interval:?}, press Enter to stop");
+
+    while !stop.load(Ordering::Relaxed) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        match device.read_words(address.area, address.word, 1) {
+            Ok(words) => match address.bit {
+                Some(bit) => println!(
+                    "{timestamp:.3}  {address}  {}",
+                    u8::from(words[0] & (1 << bit) != 0)
+                ),
+                None => println!("{timestamp:.3}  {address}  {:04X} ({})", words[0], words[0]),
+            },
+            Err(error) => println!("{timestamp:.3}  {address}  error: {error}"),
+        }
+
+        let deadline = SystemTime::now() + interval;
+        while !stop.load(Ordering::Relaxed) && SystemTime::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    let _ = stopper.join();
+
+    Ok(())
+}
+
+/// Builds a command from either a header code with parameters, or a symbolic command.
+fn to_request(node: NodeId, command: &str, args: &[&str]) -> Result<Request, ReplError> {
+    if let Ok(kind) = MessageKind::from_str(command) {
+        let params = MessageParams::from_slice(args.concat().as_bytes())?;
+        return Ok(Request::new(node, kind, params));
+    }
+
+    let invalid = || ReplError::Usage(format!("{command} {}", args.join(" ")).trim_end().into());
+    let address = |index: usize| -> Result<Address, ReplError> {
+        Ok(Address::from_str(
+            args.get(index).copied().ok_or_else(invalid)?,
+        )?)
+    };
+
+    let command = match (command.to_ascii_lowercase().as_str(), args) {
+        ("test", []) => EasyCommand::make_test("!rust!")?,
+        ("status", []) => EasyCommand::make_status_read(),
+        ("model", []) => EasyCommand::make_model_read(),
+        ("errors", []) => EasyCommand::make_error_read(false),
+        ("errors", ["clear"]) => EasyCommand::make_error_read(true),
+        ("read", [_] | [_, _]) => {
+            let address = address(0)?;
+            let count: u16 = args
+                .get(1)
+                .map_or(Ok(1), |count| count.parse())
+                .map_err(|_| invalid())?;
+
+            EasyCommand::make_area_read(address.area, address.word, count)?
+        }
+        ("write", [_, values @ ..]) if !values.is_empty() => {
+            let address = address(0)?;
+            // writing a bit would overwrite the whole word, `force set/reset` is used instead
+            if address.bit.is_some() {
+                return Err(invalid());
+            }
+            let values = values
+                .iter()
+                .map(|value| match value.strip_prefix("0x") {
+                    Some(hex) => u16::from_str_radix(hex, 16),
+                    None => value.parse(),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?;
+
+            EasyCommand::make_area_write(address.area, address.word, &values)?
+        }
+        ("mode", [mode]) => {
+            let mode = match mode.to_ascii_lowercase().as_str() {
+                "program" => StatusMode::Program,
+                "monitor" => StatusMode::Monitor,
+                "run" => StatusMode::Run,
+                _ => return Err(invalid()),
+            };
+
+            EasyCommand::make_status_write(mode)
+        }
+        ("force", ["cancel"]) => EasyCommand::make_forced_cancel(),
+        ("force", [action @ ("set" | "reset"), _]) => {
+            EasyCommand::make_forced(address(1)?, *action == "set")?
+        }
+        _ => return Err(invalid()),
+    };
+
+    Ok(Request::from(command.into_message(node)))
+}
+
+/// Returns the FCS of a serialized frame, if it has one (INITIALIZE doesn't).
+fn frame_fcs(frame: &str) -> Option<&str> {
+    let kind = MessageRef::decode(frame.as_bytes()).ok()?.kind();
+    if kind == MessageKind::Initialize {
+        return None;
+    }
+
+    frame.get(frame.len() - 4..frame.len() - 2)
+}
+
+fn parse_interval(interval: &str) -> Result<Duration, ReplError> {
+    let invalid = || ReplError::Interval(interval.into());
+
+    let (value, unit) = interval
+        .find(|ch: char| !ch.is_ascii_digit())
+        .map_or((interval, "s"), |index| interval.split_at(index));
+    let value: u64 = value.parse().map_err(|_| invalid())?;
+
+    let interval = match unit {
+        "ms" => Duration::from_millis(value),
+        "s" => Duration::from_secs(value),
+        "m" => Duration::from_secs(value.saturating_mul(60)),
+        _ => return Err(invalid()),
+    };
+
+    if interval < MIN_WATCH_INTERVAL {
+        return Err(invalid());
+    }
+
+    Ok(interval)
+}
+
+fn history_file() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".hostlink_history"))
+}
@@ -0,0 +1,94 @@
+use crate::output::Format;
+use hostlink::{
+    analyzer::{Analyzer, Frame},
+    device::{Error, SerialPortBuilder},
+};
+use serde_json::json;
+use std::{
+    io::{ErrorKind, Read},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How often the analyzer is checked for unterminated frames while the line is quiet.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Reads the tap port until it's closed, printing every frame seen.
+pub fn run(
+    builder: SerialPortBuilder,
+    format: Format,
+    gap: Duration,
+    latency: Duration,
+) -> Result<(), Error> {
+    let mut port = builder.timeout(POLL_INTERVAL).open()?;
+    let mut analyzer = Analyzer::new().with_gap(gap).with_latency_limit(latency);
+    let mut buffer = [0; 256];
+
+    loop {
+        let frames = match port.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => analyzer.feed(&buffer[..read], SystemTime::now()),
+            Err(error) if error.kind() == ErrorKind::TimedOut => {
+                analyzer.idle(SystemTime::now()).into_iter().collect()
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        for frame in frames {
+            print(format, &frame);
+        }
+    }
+}
+
+fn print(format: Format, frame: &Frame) {
+    let issues: Vec<String> = frame.issues.iter().map(ToString::to_string).collect();
+
+    match format {
+        Format::Json => println!(
+            "{}",
+            json!({
+                "timestamp": frame
+                    .timestamp
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64(),
+                "gap_ms": frame.gap.map(|gap| gap.as_millis()),
+                "direction": frame.direction.to_string(),
+                "raw": frame.raw,
+                "decoded": frame.describe(),
+                "issues": issues,
+            })
+        ),
+        Format::Table => {
+            let gap = frame
+                .gap
+                .map_or_else(|| "-".into(), |gap| format!("+{}ms", gap.as_millis()));
+            let issues = if issues.is_empty() {
+                String::new()
+            } else {
+                format!("  !! {}", issues.join(", "))
+            };
+
+            println!(
+                "{}  {gap:>8}  {:<4}  {:<32}  {}{issues}",
+                time_of_day(frame.timestamp),
+                frame.direction.to_string(),
+                frame.raw.escape_debug().to_string(),
+                frame.describe(),
+            );
+        }
+    }
+}
+
+/// Formats a timestamp as `HH:MM:SS.mmm` (UTC).
+fn time_of_day(timestamp: SystemTime) -> String {
+    let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs() % 86_400;
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        since_epoch.subsec_millis()
+    )
+}
@@ -0,0 +1,223 @@
+mod replay;
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufReader, Read, Write},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+pub use replay::{Replay, ReplayTransport};
+
+/// Bytes at the start of every capture file: a magic number followed by the format version.
+const HEADER: [u8; 6] = *b"HLCAP\x01";
+
+/// Length of a record's direction, timestamp and length fields.
+const RECORD_HEADER_LENGTH: usize = 11;
+
+/// Which way a frame travelled, as seen from the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    /// Sent by the host.
+    Sent,
+    /// Received from the PLC.
+    Received,
+}
+
+/// A single recorded frame.
+///
+/// Received frames which were cut short (e.g. by a timeout) are recorded as far as they arrived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub direction: Direction,
+    pub timestamp: SystemTime,
+    pub bytes: Vec<u8>,
+}
+
+/// An error that can occur while reading a capture.
+#[derive(Debug, Error)]
+pub enum CaptureError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    /// The data doesn't start with a capture header
+    #[error("Not a capture file")]
+    BadMagic,
+    /// The capture was written by a newer version of this library
+    #[error("Unsupported capture version: {0}")]
+    UnsupportedVersion(u8),
+    /// A record has an unknown direction
+    #[error("Invalid direction: {0}")]
+    InvalidDirection(u8),
+    /// The last record ends prematurely
+    #[error("Capture ends in the middle of a record")]
+    Truncated,
+}
+
+/// Writes records into a capture.
+///
+/// Every record is stored as a direction byte, a timestamp (microseconds since the Unix epoch, u64 LE),
+/// a length (u16 LE) and the frame's bytes. Records are written with a single call,
+/// so a capture stays readable up to the last record if the program is interrupted.
+#[derive(Debug)]
+pub struct CaptureWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Starts a new capture, writing the header into `inner`.
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(&HEADER)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Appends a record.
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let length = u16::try_from(record.bytes.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too long"))?;
+        let micros = record
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros();
+
+        let mut buffer = Vec::with_capacity(RECORD_HEADER_LENGTH + record.bytes.len());
+        buffer.push(match record.direction {
+            Direction::Sent => 0,
+            Direction::Received => 1,
+        });
+        buffer.extend_from_slice(&u64::try_from(micros).unwrap_or(u64::MAX).to_le_bytes());
+        buffer.extend_from_slice(&length.to_le_bytes());
+        buffer.extend_from_slice(&record.bytes);
+
+        self.inner.write_all(&buffer)?;
+        self.inner.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write + Send + 'static> CaptureWriter<W> {
+    pub(crate) fn boxed(self) -> CaptureWriter<Box<dyn Write + Send>> {
+        CaptureWriter {
+            inner: Box::new(self.inner),
+        }
+    }
+}
+
+impl CaptureWriter<File> {
+    /// Opens a capture file for appending, creating it if it doesn't exist.
+    ///
+    /// An existing file must be a capture of this version. A record cut short at its end
+    /// (e.g. because the program was killed while writing it) is removed first.
+    pub fn append(path: impl AsRef<Path>) -> Result<Self, CaptureError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::new(file)?);
+        }
+
+        let mut end = HEADER.len();
+        for record in CaptureReader::new(BufReader::new(&file))? {
+            match record {
+                Ok(record) => end += RECORD_HEADER_LENGTH + record.bytes.len(),
+                Err(CaptureError::Truncated) => {
+                    file.set_len(end as u64)?;
+                    break;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(Self { inner: file })
+    }
+}
+
+/// Reads records from a capture.
+#[derive(Debug)]
+pub struct CaptureReader<R> {
+    inner: R,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Checks the capture's header and returns a reader positioned at the first record.
+    pub fn new(mut inner: R) -> Result<Self, CaptureError> {
+        let mut header = [0; HEADER.len()];
+        inner
+            .read_exact(&mut header)
+            .map_err(|error| match error.kind() {
+                io::ErrorKind::UnexpectedEof => CaptureError::BadMagic,
+                _ => error.into(),
+            })?;
+
+        if header[..5] != HEADER[..5] {
+            return Err(CaptureError::BadMagic);
+        }
+
+        if header[5] != HEADER[5] {
+            return Err(CaptureError::UnsupportedVersion(header[5]));
+        }
+
+        Ok(Self { inner })
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, CaptureError> {
+        let mut direction = [0];
+        if self.inner.read(&mut direction)? == 0 {
+            return Ok(None);
+        }
+
+        let direction = match direction[0] {
+            0 => Direction::Sent,
+            1 => Direction::Received,
+            other => return Err(CaptureError::InvalidDirection(other)),
+        };
+
+        let mut fixed = [0; 10];
+        self.read_exact(&mut fixed)?;
+
+        let micros = u64::from_le_bytes(fixed[..8].try_into().unwrap_or_default());
+        let length = u16::from_le_bytes([fixed[8], fixed[9]]);
+
+        let mut bytes = vec![0; length.into()];
+        self.read_exact(&mut bytes)?;
+
+        Ok(Some(Record {
+            direction,
+            timestamp: UNIX_EPOCH + Duration::from_micros(micros),
+            bytes,
+        }))
+    }
+
+    fn read_exact(&mut self, buffer: &mut [u8]) -> Result<(), CaptureError> {
+        self.inner
+            .read_exact(buffer)
+            .map_err(|error| match error.kind() {
+                io::ErrorKind::UnexpectedEof => CaptureError::Truncated,
+                _ => error.into(),
+            })
+    }
+}
+
+impl CaptureReader<BufReader<File>> {
+    /// Opens a capture file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, CaptureError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<Record, CaptureError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
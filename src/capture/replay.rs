@@ -0,0 +1,124 @@
+use super::{CaptureError, CaptureReader, Direction, Record};
+use crate::device::transport::{MemoryTransport, Reply, Responder};
+use std::{collections::VecDeque, path::Path, time::Duration};
+
+/// A transport which plays back a capture.
+pub type ReplayTransport = MemoryTransport<Replay>;
+
+/// A [`Responder`] which answers commands with the responses recorded in a capture.
+///
+/// Recorded exchanges are played back in order, one per command, regardless of the command's contents.
+/// Commands which differ from the recorded ones are counted as [`mismatches`](Self::mismatches).
+/// Commands which went unanswered in the capture are not answered either.
+/// # Example
+/// ```rust,no_run
+/// use hostlink::{
+///     capture::{Replay, ReplayTransport},
+///     device::PlcDevice,
+///     protocol::NodeId,
+/// };
+///
+/// let replay = Replay::open("field.hlcap").unwrap().with_timing();
+/// let mut device = PlcDevice::with_transport(Box::new(ReplayTransport::new(replay)), NodeId::new(0).unwrap());
+///
+/// device.status().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    exchanges: VecDeque<Exchange>,
+    timing: bool,
+    mismatches: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Exchange {
+    command: Vec<u8>,
+    response: Vec<u8>,
+    delay: Duration,
+}
+
+impl Replay {
+    /// Creates a replay from recorded frames.
+    /// Received frames which don't follow a sent frame are skipped.
+    pub fn new(records: impl IntoIterator<Item = Record>) -> Self {
+        let mut exchanges: VecDeque<Exchange> = VecDeque::new();
+        let mut sent = None;
+
+        for record in records {
+            match record.direction {
+                Direction::Sent => {
+                    sent = Some(record.timestamp);
+                    exchanges.push_back(Exchange {
+                        command: record.bytes,
+                        response: Vec::new(),
+                        delay: Duration::ZERO,
+                    });
+                }
+                Direction::Received => {
+                    let (Some(sent), Some(exchange)) = (sent, exchanges.back_mut()) else {
+                        continue;
+                    };
+
+                    if exchange.response.is_empty() {
+                        exchange.delay = record.timestamp.duration_since(sent).unwrap_or_default();
+                    }
+
+                    exchange.response.extend(record.bytes);
+                }
+            }
+        }
+
+        Self {
+            exchanges,
+            timing: false,
+            mismatches: 0,
+        }
+    }
+
+    /// Reads a capture file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, CaptureError> {
+        let records = CaptureReader::open(path)?.collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(records))
+    }
+
+    /// Delays responses by as long as they took in the capture, so that timeouts are reproduced.
+    #[must_use]
+    pub const fn with_timing(mut self) -> Self {
+        self.timing = true;
+        self
+    }
+
+    /// Returns the number of exchanges which haven't been played back yet.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.exchanges.len()
+    }
+
+    /// Returns the number of commands which differed from the recorded ones.
+    #[must_use]
+    pub const fn mismatches(&self) -> usize {
+        self.mismatches
+    }
+}
+
+impl Responder for Replay {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        let Some(exchange) = self.exchanges.pop_front() else {
+            return Reply::default();
+        };
+
+        if exchange.command != frame {
+            self.mismatches += 1;
+        }
+
+        Reply {
+            bytes: exchange.response,
+            delay: if self.timing {
+                exchange.delay
+            } else {
+                Duration::ZERO
+            },
+        }
+    }
+}
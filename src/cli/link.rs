@@ -0,0 +1,114 @@
+use crate::{
+    device::{
+        DataBits, Error, FlowControl, PlcDevice, Rfc2217Transport, SerialPortBuilder,
+        SerialSettings, StopBits, TcpTransport,
+    },
+    protocol::NodeId,
+};
+use clap::{Args, ValueEnum};
+use std::time::Duration;
+
+/// Serial link settings. The defaults match the PLC's factory settings.
+#[derive(Debug, Args)]
+pub struct SerialArgs {
+    /// Serial port (e.g. /dev/ttyUSB0 or COM3), or a serial device server
+    /// (tcp://host:port for raw TCP, rfc2217://host:port for RFC 2217)
+    #[arg(short, long, global = true, default_value = "/dev/ttyUSB0")]
+    pub port: String,
+
+    /// Baud rate
+    #[arg(short, long, global = true, default_value_t = 9600)]
+    pub baud: u32,
+
+    /// Parity
+    #[arg(long, global = true, value_enum, default_value_t = Parity::Even)]
+    pub parity: Parity,
+
+    /// Number of data bits
+    #[arg(long, global = true, default_value_t = 7, value_parser = clap::value_parser!(u8).range(5..=8))]
+    pub data_bits: u8,
+
+    /// Number of stop bits
+    #[arg(long, global = true, default_value_t = 2, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub stop_bits: u8,
+
+    /// Response timeout in milliseconds
+    #[arg(long, global = true, default_value_t = 3000)]
+    pub timeout: u64,
+
+    /// Number of retries after link errors
+    #[arg(long, global = true, default_value_t = 0)]
+    pub retries: u8,
+}
+
+/// Parity of the serial link.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Parity {
+    None,
+    Odd,
+    Even,
+}
+
+impl SerialArgs {
+    /// Returns a builder for the configured serial port.
+    pub fn builder(&self) -> SerialPortBuilder {
+        let settings = self.settings();
+
+        serialport::new(&self.port, settings.baud)
+            .data_bits(settings.data_bits)
+            .flow_control(FlowControl::None)
+            .parity(settings.parity)
+            .stop_bits(settings.stop_bits)
+    }
+
+    /// Returns the configured serial settings.
+    pub fn settings(&self) -> SerialSettings {
+        let parity = match self.parity {
+            Parity::None => serialport::Parity::None,
+            Parity::Odd => serialport::Parity::Odd,
+            Parity::Even => serialport::Parity::Even,
+        };
+        let data_bits = match self.data_bits {
+            5 => DataBits::Five,
+            6 => DataBits::Six,
+            7 => DataBits::Seven,
+            _ => DataBits::Eight,
+        };
+        let stop_bits = match self.stop_bits {
+            1 => StopBits::One,
+            _ => StopBits::Two,
+        };
+
+        SerialSettings {
+            baud: self.baud,
+            data_bits,
+            parity,
+            stop_bits,
+        }
+    }
+
+    /// Opens the serial port (or connects to the serial device server) and connects to `node`.
+    pub fn connect(&self, node: NodeId) -> Result<PlcDevice, Error> {
+        let timeout = Duration::from_millis(self.timeout);
+
+        let mut device = if let Some(address) = self.port.strip_prefix("tcp://") {
+            let transport = TcpTransport::connect(address, timeout)?;
+            PlcDevice::with_transport(Box::new(transport), node)
+        } else if let Some(address) = self.port.strip_prefix("rfc2217://") {
+            let transport = Rfc2217Transport::connect(address, self.settings(), timeout)?;
+            PlcDevice::with_transport(Box::new(transport), node)
+        } else {
+            PlcDevice::connect_with_builder(self.builder(), node, Some(timeout))?
+        };
+        device.set_retries(self.retries);
+
+        Ok(device)
+    }
+}
+
+/// Parses a node ID given on the command line.
+pub fn parse_node(value: &str) -> Result<NodeId, String> {
+    let value = value.parse().map_err(|error| format!("{error}"))?;
+
+    NodeId::new(value).map_err(|error| error.to_string())
+}
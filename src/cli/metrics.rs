@@ -0,0 +1,158 @@
+use super::Tag;
+use crate::{
+    device::{Error, PlcDevice},
+    protocol::{responses::status::StatusMode, NodeId},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+};
+
+/// Reads all tags and keeps track of communication failures.
+pub struct Poller {
+    tags: Vec<Tag>,
+    nodes: BTreeSet<NodeId>,
+    failures: BTreeMap<NodeId, u64>,
+}
+
+impl Poller {
+    /// Creates a poller for `tags`, which may be spread over several nodes.
+    #[must_use]
+    pub fn new(tags: Vec<Tag>) -> Self {
+        let nodes = tags.iter().map(|tag| tag.node).collect();
+
+        Self {
+            tags,
+            nodes,
+            failures: BTreeMap::new(),
+        }
+    }
+
+    /// Polls every node once and renders the results as Prometheus metrics.
+    ///
+    /// `on_error` is called for every failed status or tag read.
+    pub fn poll(
+        &mut self,
+        device: &mut PlcDevice,
+        mut on_error: impl FnMut(NodeId, &Error),
+    ) -> String {
+        let mut up = String::new();
+        let mut modes = String::new();
+        let mut fals = String::new();
+        let mut errors = String::new();
+        let mut values = String::new();
+
+        for node in self.nodes.clone() {
+            device.set_node_id(node);
+
+            let status = device.status();
+            let labels = format!("node=\"{node}\"");
+            let _ = writeln!(up, "hostlink_up{{{labels}}} {}", u8::from(status.is_ok()));
+
+            match status {
+                Ok(status) => {
+                    for mode in [StatusMode::Program, StatusMode::Monitor, StatusMode::Run] {
+                        let _ = writeln!(
+                            modes,
+                            "hostlink_plc_mode{{{labels},mode=\"{}\"}} {}",
+                            mode.to_string().to_uppercase(),
+                            u8::from(status.mode == mode)
+                        );
+                    }
+
+                    let _ = writeln!(
+                        fals,
+                        "hostlink_plc_fals{{{labels}}} {}",
+                        u8::from(status.fals)
+                    );
+                    let _ = writeln!(
+                        errors,
+                        "hostlink_plc_error{{{labels}}} {}",
+                        u8::from(status.error)
+                    );
+                }
+                Err(error) => self.failed(node, &error, &mut on_error),
+            }
+
+            let mut failures = Vec::new();
+
+            for tag in self.tags.iter().filter(|tag| tag.node == node) {
+                let address = tag.address;
+
+                match device.read_words(address.area, address.word, 1) {
+                    Ok(words) => {
+                        let value = address.bit.map_or(words[0], |bit| (words[0] >> bit) & 1);
+
+                        let _ = writeln!(
+                            values,
+                            "hostlink_tag_value{{tag=\"{}\",{labels},address=\"{address}\"}} {value}",
+                            tag.name
+                        );
+                    }
+                    Err(error) => failures.push(error),
+                }
+            }
+
+            for error in failures {
+                self.failed(node, &error, &mut on_error);
+            }
+        }
+
+        let failures: String = self
+            .failures
+            .iter()
+            .map(|(node, count)| {
+                format!("hostlink_comm_failures_total{{node=\"{node}\"}} {count}\n")
+            })
+            .collect();
+
+        let families = [
+            (
+                "hostlink_up",
+                "gauge",
+                "Whether the PLC answered the last status read",
+                up,
+            ),
+            (
+                "hostlink_plc_mode",
+                "gauge",
+                "The PLC's operation mode",
+                modes,
+            ),
+            (
+                "hostlink_plc_fals",
+                "gauge",
+                "Whether a FALS was generated",
+                fals,
+            ),
+            (
+                "hostlink_plc_error",
+                "gauge",
+                "Whether a fatal error was generated",
+                errors,
+            ),
+            ("hostlink_tag_value", "gauge", "Value of a tag", values),
+            (
+                "hostlink_comm_failures_total",
+                "counter",
+                "Failed status and tag reads",
+                failures,
+            ),
+        ];
+
+        let mut output = String::new();
+
+        for (name, kind, help, samples) in families {
+            let _ = writeln!(output, "# HELP {name} {help}\n# TYPE {name} {kind}");
+            output.push_str(&samples);
+        }
+
+        output.push_str(&device.stats().snapshot().to_prometheus());
+        output
+    }
+
+    fn failed(&mut self, node: NodeId, error: &Error, on_error: &mut impl FnMut(NodeId, &Error)) {
+        on_error(node, error);
+        *self.failures.entry(node).or_default() += 1;
+    }
+}
@@ -0,0 +1,7 @@
+mod link;
+mod metrics;
+mod tags;
+
+pub use link::{parse_node, Parity, SerialArgs};
+pub use metrics::Poller;
+pub use tags::{load_tags, Tag};
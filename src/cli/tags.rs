@@ -0,0 +1,58 @@
+use super::parse_node;
+use crate::protocol::{Address, NodeId};
+use std::{fs, path::Path, str::FromStr};
+
+/// A PLC address exported as a gauge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub node: NodeId,
+    pub address: Address,
+}
+
+/// Loads a tag list.
+///
+/// Every line contains a tag name, a node ID and an address, separated by whitespace
+/// (e.g. `tank_level 0 DM0100`). Empty lines and lines starting with `#` are ignored.
+pub fn load_tags(path: &Path) -> Result<Vec<Tag>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            line.parse()
+                .map_err(|error| format!("{}:{number}: {error}", path.display()))
+        })
+        .collect()
+}
+
+impl FromStr for Tag {
+    type Err = String;
+
+    /// Parses a single line of a tag list.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let [name, node, address] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err("expected `name node address`".into());
+        };
+
+        if !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '.' || ch == '-')
+        {
+            return Err(format!("invalid tag name '{name}'"));
+        }
+
+        let node = parse_node(node).map_err(|error| format!("invalid node '{node}': {error}"))?;
+        let address = address.parse().map_err(|error| format!("{error}"))?;
+
+        Ok(Self {
+            name: name.into(),
+            node,
+            address,
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use super::{DeviceError, Error};
+use crate::protocol::{responses::model::PcModel, Area, MessageKind, ProtocolError};
+use std::collections::BTreeSet;
+
+/// The commands and areas supported by a PLC, found by
+/// [`PlcDevice::probe_capabilities`](super::PlcDevice::probe_capabilities).
+///
+/// Only the PC MODEL READ command and the area reads are probed; every other command is assumed
+/// to be supported until the PLC rejects it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    model: Option<PcModel>,
+    unsupported: BTreeSet<MessageKind>,
+}
+
+impl Capabilities {
+    /// Returns the model code, if the PLC supports reading it.
+    #[must_use]
+    pub const fn model(&self) -> Option<PcModel> {
+        self.model
+    }
+
+    /// Returns whether the PLC supports a command.
+    #[must_use]
+    pub fn supports(&self, kind: MessageKind) -> bool {
+        !self.unsupported.contains(&kind)
+    }
+
+    /// Returns whether the PLC has an area, i.e. supports reading it.
+    #[must_use]
+    pub fn supports_area(&self, area: Area) -> bool {
+        self.supports(area.read_kind())
+    }
+
+    /// Returns the commands which the PLC rejected.
+    pub fn unsupported(&self) -> impl Iterator<Item = MessageKind> + '_ {
+        self.unsupported.iter().copied()
+    }
+
+    pub(crate) fn set_model(&mut self, model: PcModel) {
+        self.model = Some(model);
+    }
+
+    /// Records the result of a probe, marking `kinds` unsupported if the PLC rejected it.
+    /// Fails if the probe wasn't answered at all.
+    ///
+    /// An undefined command ([`UnsupportedCommand`](DeviceError::UnsupportedCommand)) or
+    /// [`InstructionNotFound`](DeviceError::InstructionNotFound) means the command isn't supported,
+    /// [`AddressOver`](DeviceError::AddressOver) that the area doesn't exist on this model.
+    /// Other end codes, e.g. the PLC being in the wrong mode, don't tell anything.
+    pub(crate) fn record<T>(
+        &mut self,
+        kinds: &[MessageKind],
+        result: Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
+        let error = match result {
+            Ok(value) => return Ok(Some(value)),
+            Err(Error::Device(error) | Error::Protocol(ProtocolError::Device(error))) => error,
+            Err(error) => return Err(error),
+        };
+
+        if matches!(
+            error,
+            DeviceError::UnsupportedCommand(..)
+                | DeviceError::InstructionNotFound
+                | DeviceError::AddressOver
+        ) {
+            self.unsupported.extend(kinds);
+        }
+
+        Ok(None)
+    }
+}
@@ -1,21 +1,54 @@
+mod capabilities;
 mod error;
+mod stats;
+mod subscription;
+/// Transports that a [`PlcDevice`] can communicate over.
+pub mod transport;
 
-use crate::protocol::responses::status::Status;
-use crate::protocol::{Message, MessageKind, MessageParams, NodeId, ProtocolError};
+use crate::capture::{CaptureWriter, Direction, Record};
+use crate::fins::{FinsCommand, FinsHeader, FinsResponse};
+use crate::protocol::commands::{
+    CancelForced, Command, Force, ReadArea, ReadErrors, ReadModel, ReadStatus, WriteArea,
+    WriteStatus,
+};
+use crate::protocol::responses::{
+    model::PcModel,
+    status::{Status, StatusMode},
+};
+use crate::protocol::{
+    Address, Area, EasyCommand, FrameDecoder, FrameError, Message, MessageKind, MessageParams,
+    MessageRef, NodeId, ProtocolError, Request, Response, MAX_READ_WORDS, MAX_WRITE_WORDS,
+};
+pub use capabilities::Capabilities;
 pub use error::{DeviceError, Error};
-pub use serialport::{DataBits, FlowControl, SerialPort, SerialPortBuilder, StopBits};
+pub use serialport::{DataBits, FlowControl, Parity, SerialPort, SerialPortBuilder, StopBits};
+pub use stats::{Counters, Histogram, LinkStats, StatsSnapshot, LATENCY_BUCKETS_MS};
 use std::{
-    io::{BufRead, BufReader, BufWriter, Write},
-    time::Duration,
+    fmt::Debug,
+    io::{self, Write},
+    sync::{mpsc::Receiver, Arc},
+    time::{Duration, Instant, SystemTime},
 };
+use subscription::Subscription;
+pub use subscription::{Quality, Value, ValueChange};
+pub use transport::{Rfc2217Transport, SerialSettings, TcpTransport, Transport};
 
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(3);
 
-#[derive(Debug)]
 pub struct PlcDevice {
-    reader: BufReader<Box<dyn SerialPort>>,
-    writer: BufWriter<Box<dyn SerialPort>>,
+    port: Box<dyn Transport>,
     node_id: NodeId,
+    retries: u8,
+    subscriptions: Vec<Subscription>,
+    capture: Option<CaptureWriter<Box<dyn Write + Send>>>,
+    stats: Arc<LinkStats>,
+    /// Service ID of the last FINS command
+    fins_sid: u8,
+    /// Frame being sent, kept to avoid allocating for every transaction
+    buffer: Vec<u8>,
+    decoder: FrameDecoder,
+    /// What the PLC supports, if it was probed
+    capabilities: Option<Capabilities>,
 }
 
 impl PlcDevice {
@@ -26,14 +59,37 @@ impl PlcDevice {
     ) -> Result<Self, Error> {
         port.set_timeout(timeout.unwrap_or(DEFAULT_TIMEOUT))?;
 
-        let reader = port;
-        let writer = reader.try_clone()?;
+        Ok(Self::with_transport(Box::new(port), node_id))
+    }
 
-        Ok(Self {
-            reader: BufReader::new(reader),
-            writer: BufWriter::new(writer),
+    /// Creates a device which communicates over an arbitrary transport.
+    /// The transport is responsible for timing out reads.
+    #[must_use]
+    pub fn with_transport(transport: Box<dyn Transport>, node_id: NodeId) -> Self {
+        Self {
+            port: transport,
             node_id,
-        })
+            retries: 0,
+            subscriptions: Vec::new(),
+            capture: None,
+            stats: Arc::default(),
+            fins_sid: 0,
+            buffer: Vec::new(),
+            decoder: FrameDecoder::new(),
+            capabilities: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// Changes the node that commands are sent to, e.g. to poll several PLCs sharing a multidrop link.
+    /// Forgets the probed capabilities of the previous node.
+    pub fn set_node_id(&mut self, node_id: NodeId) {
+        self.node_id = node_id;
+        self.capabilities = None;
     }
 
     pub fn connect_with_builder(
@@ -44,73 +100,437 @@ impl PlcDevice {
         Self::connect(builder.open()?, node_id, timeout)
     }
 
+    /// Sets how many times a command is sent again after failing due to a link error
+    /// (timeout, corrupted response, transmission error reported by the PLC, etc.).
+    pub fn set_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
+    /// Returns the link statistics collected by this device.
+    #[must_use]
+    pub const fn stats(&self) -> &Arc<LinkStats> {
+        &self.stats
+    }
+
+    /// Collects link statistics into `stats`, which may be shared with other devices.
+    pub fn set_stats(&mut self, stats: Arc<LinkStats>) {
+        self.stats = stats;
+    }
+
+    /// Records every frame sent and received into a capture, or stops recording if `capture` is `None`.
+    ///
+    /// Recording stops if writing to the capture fails; transactions aren't affected.
+    pub fn set_capture<W: Write + Send + 'static>(&mut self, capture: Option<CaptureWriter<W>>) {
+        self.capture = capture.map(CaptureWriter::boxed);
+    }
+
+    /// Returns whether frames are being recorded into a capture.
+    #[must_use]
+    pub const fn is_capturing(&self) -> bool {
+        self.capture.is_some()
+    }
+
     pub fn test(&mut self) -> Result<(), Error> {
-        let params: MessageParams = "!rust!".into();
-        let command = Message::new(self.node_id, MessageKind::Test, params);
+        let params = MessageParams::from_slice(b"!rust!")?;
+        let command = Request::new(self.node_id, MessageKind::Test, params.clone());
 
-        self._send_commnad(command.clone())?;
-        let response = self._await_response()?;
+        let response = self._send_command_and_await_response(command, false)?;
 
-        if response == command {
+        if response.data() == &params {
             return Ok(());
         }
 
-        let dev_err = response.as_device_error()?;
-        dev_err.to_result()?;
+        // a PLC which fails to execute the command sends an end code instead of the data
+        let data = String::from_utf8_lossy(response.data());
+        DeviceError::try_from(&*data)?.to_result()?;
 
-        unreachable!()
+        Err(Error::UnexpectedResponse(response.node(), response.kind()))
     }
 
     pub fn status(&mut self) -> Result<Status, Error> {
-        let response = self._send_command_and_await_response(
-            Message::new_with_empty_params(self.node_id, MessageKind::StatusRead),
-            true,
-        )?;
+        self.execute(&ReadStatus)
+    }
+
+    /// Reads `count` words from `area`, starting at word `start`.
+    /// Large reads are split into multiple commands, so that every response fits into a single frame.
+    pub fn read_words(&mut self, area: Area, start: u16, count: u16) -> Result<Vec<u16>, Error> {
+        let mut words = Vec::with_capacity(count.into());
+
+        while words.len() < count.into() {
+            #[allow(clippy::cast_possible_truncation)]
+            let offset = words.len() as u16;
+            let chunk = (count - offset).min(MAX_READ_WORDS);
 
-        let status = Status::try_from(response).map_err(ProtocolError::StatusParse)?;
+            words.extend(self.execute(&ReadArea::new(area, start + offset, chunk)?)?);
+        }
 
-        Ok(status)
+        Ok(words)
+    }
+
+    /// Writes words into `area`, starting at word `start`.
+    /// Large writes are split into multiple commands, so that every command fits into a single frame.
+    pub fn write_words(&mut self, area: Area, start: u16, data: &[u16]) -> Result<(), Error> {
+        for (index, chunk) in data.chunks(MAX_WRITE_WORDS).enumerate() {
+            #[allow(clippy::cast_possible_truncation)]
+            let offset = (index * MAX_WRITE_WORDS) as u16;
+            self.execute(&WriteArea::new(area, start + offset, chunk)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the PLC's model code.
+    pub fn model(&mut self) -> Result<PcModel, Error> {
+        self.execute(&ReadModel)
+    }
+
+    /// Reads the PLC's error information. If `clear` is set, errors are cleared after reading.
+    pub fn errors(&mut self, clear: bool) -> Result<Vec<u16>, Error> {
+        self.execute(&ReadErrors { clear })
+    }
+
+    /// Changes the PLC's operation mode.
+    pub fn set_mode(&mut self, mode: StatusMode) -> Result<(), Error> {
+        self.execute(&WriteStatus(mode))
+    }
+
+    /// Force-sets (`set == true`) or force-resets a bit.
+    pub fn force(&mut self, address: Address, set: bool) -> Result<(), Error> {
+        self.execute(&Force::new(address, set)?)
+    }
+
+    /// Cancels all forced set/reset bits.
+    pub fn cancel_forced(&mut self) -> Result<(), Error> {
+        self.execute(&CancelForced)
+    }
+
+    /// Cancels a multi-frame exchange with the PLC using the ABORT (`XZ`) command.
+    pub fn abort(&mut self) -> Result<(), Error> {
+        self._send_commnad(Request::from(
+            EasyCommand::make_abort().into_message(self.node_id),
+        ))
+    }
+
+    /// Resets the transmission control of every unit on the line using the INITIALIZE (`**`) command.
+    ///
+    /// It's never sent automatically, since it affects the other units on a multidrop line too.
+    pub fn initialize(&mut self) -> Result<(), Error> {
+        self._send_commnad(Request::from(
+            EasyCommand::make_initialize().into_message(self.node_id),
+        ))
+    }
+
+    /// Finds out which commands and areas the PLC supports, by reading its model code and a word from
+    /// every area. Nothing is written.
+    ///
+    /// The result is cached: afterwards, commands which the PLC is known not to support fail with
+    /// [`Error::NotSupported`] without being sent. [`send_command`](Self::send_command) isn't checked.
+    pub fn probe_capabilities(&mut self) -> Result<&Capabilities, Error> {
+        self.capabilities = None;
+        let mut capabilities = Capabilities::default();
+
+        let model = self.execute(&ReadModel);
+        if let Some(model) = capabilities.record(&[MessageKind::PcModelRead], model)? {
+            capabilities.set_model(model);
+        }
+
+        for area in Area::ALL {
+            let words = self.execute(&ReadArea::new(area, 0, 1)?);
+            capabilities.record(&[area.read_kind(), area.write_kind()], words)?;
+        }
+
+        debug!(node = %self.node_id, ?capabilities, "capabilities probed");
+
+        Ok(self.capabilities.insert(capabilities))
+    }
+
+    /// Returns the capabilities found by [`probe_capabilities`](Self::probe_capabilities).
+    #[must_use]
+    pub const fn capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_ref()
+    }
+
+    /// Sends a command and decodes its response into the command's response type.
+    /// # Example
+    /// ```rust,ignore
+    /// let status: Status = device.execute(&ReadStatus)?;
+    /// let words: Vec<u16> = device.execute(&ReadArea::new(Area::Dm, 100, 4)?)?;
+    /// ```
+    pub fn execute<C: Command>(&mut self, command: &C) -> Result<C::Response, Error> {
+        let response = self._send_easy(command.command())?;
+
+        Ok(C::decode(&response)?)
+    }
+
+    /// Subscribes to changes of a word or bit address.
+    ///
+    /// Word values are only reported when they change by more than `deadband`,
+    /// bit values are reported whenever they flip. Changes of the value's [`Quality`] are always reported.
+    /// The address is read each time [`poll_subscriptions`](Self::poll_subscriptions) is called.
+    pub fn subscribe(&mut self, address: Address, deadband: u16) -> Receiver<ValueChange> {
+        let (subscription, receiver) = Subscription::new(address, deadband);
+        self.subscriptions.push(subscription);
+
+        receiver
+    }
+
+    /// Reads all subscribed addresses once and emits change events.
+    /// Subscriptions whose receiver was dropped are removed.
+    pub fn poll_subscriptions(&mut self) {
+        let mut subscriptions = std::mem::take(&mut self.subscriptions);
+
+        subscriptions.retain_mut(|subscription| {
+            let address = subscription.address();
+            let result = self
+                .read_words(address.area, address.word, 1)
+                .map(|words| words[0]);
+
+            subscription.update(&result, SystemTime::now())
+        });
+
+        self.subscriptions = subscriptions;
+    }
+
+    /// Sends a FINS command to the CPU unit, wrapped in a Host Link `FA` command (CS/CJ-series PLCs only).
+    ///
+    /// Every command gets a new service ID. Returns the response if its end code reports normal completion.
+    pub fn fins(&mut self, command: &FinsCommand) -> Result<FinsResponse, Error> {
+        self.fins_sid = self.fins_sid.wrapping_add(1);
+
+        self.fins_with_header(FinsHeader::new(self.fins_sid), command)
+    }
+
+    /// Sends a FINS command with the specified header, wrapped in a Host Link `FA` command.
+    ///
+    /// The response is checked to carry the same service ID and command code.
+    /// Returns the response if its end code reports normal completion.
+    pub fn fins_with_header(
+        &mut self,
+        header: FinsHeader,
+        command: &FinsCommand,
+    ) -> Result<FinsResponse, Error> {
+        let response = self
+            ._send_command_and_await_response(command.to_hostlink(self.node_id, header)?, true)?;
+        let response = FinsResponse::from_hostlink(&response).map_err(ProtocolError::FinsParse)?;
+
+        if response.header.sid != header.sid || response.command != command.code() {
+            debug!(
+                sid = response.header.sid,
+                command = response.command,
+                "unexpected FINS response"
+            );
+            return Err(Error::UnexpectedResponse(self.node_id, MessageKind::Fins));
+        }
+
+        response.end_code.to_result()?;
+
+        Ok(response)
+    }
+
+    /// Sends an arbitrary command and returns the PLC's response.
+    ///
+    /// The command is sent to this device's node. The response is checked to come from the same node
+    /// and to be of the same kind, but its end code is not checked.
+    pub fn send_command(&mut self, cmd: Request) -> Result<Response, Error> {
+        self._send_command_and_await_response(cmd, false)
+    }
+
+    /// Sends a command built using the easy API and checks the response's end code.
+    fn _send_easy(&mut self, command: EasyCommand) -> Result<Response, Error> {
+        let kind = command.kind();
+        if let Some(capabilities) = &self.capabilities {
+            if !capabilities.supports(kind) {
+                return Err(Error::NotSupported(kind));
+            }
+        }
+
+        let request = Request::from(command.into_message(self.node_id));
+
+        self._send_command_and_await_response(request, true)
     }
 
     fn _send_command_and_await_response(
         &mut self,
-        cmd: Message,
+        cmd: Request,
         error_check: bool,
-    ) -> Result<Message, Error> {
+    ) -> Result<Response, Error> {
+        debug_span!("transaction", node = %self.node_id, kind = cmd.kind().code());
+        let mut attempt = 0;
+
+        loop {
+            let started = Instant::now();
+            let result = self._transact(cmd.clone(), error_check);
+            self.stats
+                .record(self.node_id, cmd.kind(), &result, started.elapsed());
+
+            match result {
+                Err(error) if attempt < self.retries && error.is_retryable() => {
+                    attempt += 1;
+                    self.stats.record_retry(self.node_id, cmd.kind());
+                    debug!(attempt, retries = self.retries, %error, "retrying");
+
+                    // drop whatever is left of the broken response
+                    self.decoder.clear();
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn _transact(&mut self, cmd: Request, error_check: bool) -> Result<Response, Error> {
+        let kind = cmd.kind();
+        #[cfg(feature = "tracing")]
+        let started = Instant::now();
         self._send_commnad(cmd)?;
 
-        if error_check {
-            self._await_response_and_err_check()
+        let msg = self._await_response()?;
+
+        // the PLC doesn't support the command, and sent IC without saying which command it was
+        let response = if msg.node() == self.node_id && msg.kind() == MessageKind::UndefinedCommand
+        {
+            let end_code = DeviceError::UnsupportedCommand(kind);
+            Response::new(msg.node(), kind, end_code, MessageParams::default())
         } else {
-            self._await_response()
+            if msg.node() != self.node_id || msg.kind() != kind {
+                debug!(node = %msg.node(), kind = msg.kind().code(), "unexpected response");
+                return Err(Error::UnexpectedResponse(msg.node(), msg.kind()));
+            }
+
+            Response::try_from(msg).inspect_err(|_error| {
+                debug!(error = %_error, "response has no valid end code");
+            })?
+        };
+
+        debug!(
+            latency_us = started.elapsed().as_micros(),
+            end_code = %response.end_code().code(),
+            "response received"
+        );
+
+        if error_check {
+            if let Some(error) = response.error() {
+                return Err(Error::Device(error));
+            }
         }
+
+        Ok(response)
     }
 
-    fn _send_commnad(&mut self, mut cmd: Message) -> Result<(), Error> {
+    fn _send_commnad(&mut self, mut cmd: Request) -> Result<(), Error> {
         cmd.set_node_id(self.node_id);
-        self.writer.write_all(cmd.serialize()?.as_bytes())?;
-        self.writer.flush()?;
+
+        self.buffer.clear();
+        Message::from(cmd).encode_to_vec(&mut self.buffer)?;
+        trace!(frame = %self.buffer.escape_ascii(), "sending");
+        record(&mut self.capture, Direction::Sent, &self.buffer);
+
+        self.port.write_all(&self.buffer)?;
+        self.port.flush()?;
 
         Ok(())
     }
 
-    fn _await_response_and_err_check(&mut self) -> Result<Message, Error> {
-        let msg = self._await_response()?;
+    fn _await_response(&mut self) -> Result<Message, Error> {
+        let mut chunk = [0; 256];
 
-        if let Some(error) = msg.check_device_error() {
-            return Err(Error::Device(error));
-        }
+        loop {
+            match self.decoder.next_frame() {
+                Some(Ok(frame)) => {
+                    record(&mut self.capture, Direction::Received, frame);
+                    trace!(frame = %frame.escape_ascii(), "received");
 
-        Ok(msg)
+                    let msg = match MessageRef::decode(frame) {
+                        Ok(msg) => msg,
+                        // only the last frame of a multi-frame response ends with `*`
+                        Err(ProtocolError::MissingTerminator) => {
+                            debug!("multi-frame response, aborting");
+                            self.decoder.clear();
+
+                            // the exchange failed either way, report why
+                            if let Err(_error) = self.abort() {
+                                debug!(error = %_error, "abort failed");
+                            }
+
+                            return Err(Error::MultiFrameResponse(self.node_id));
+                        }
+                        Err(error) => {
+                            debug!(%error, "response could not be parsed");
+                            return Err(error.into());
+                        }
+                    };
+
+                    return Ok(msg.to_message()?);
+                }
+                Some(Err(_error @ (FrameError::Noise(..) | FrameError::Unterminated(..)))) => {
+                    debug!(error = %_error, "resynchronizing");
+                    continue;
+                }
+                Some(Err(error)) => {
+                    debug!(%error, "response dropped");
+                    return Err(ProtocolError::from(error).into());
+                }
+                None => (),
+            }
+
+            let read = match self.port.read(&mut chunk) {
+                Ok(0) => Err(io::ErrorKind::UnexpectedEof.into()),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                read => read,
+            };
+
+            match read {
+                Ok(count) => {
+                    self.decoder.push(&chunk[..count]);
+                }
+                Err(error) => {
+                    let pending = self.decoder.pending();
+
+                    // partial frames are recorded as well, so that timeouts can be replayed
+                    if !pending.is_empty() {
+                        record(&mut self.capture, Direction::Received, pending);
+                        trace!(frame = %pending.escape_ascii(), "received");
+                    }
+
+                    debug!(%error, received = pending.len(), "no complete response");
+                    self.decoder.clear();
+
+                    return Err(error.into());
+                }
+            }
+        }
     }
+}
 
-    fn _await_response(&mut self) -> Result<Message, Error> {
-        let mut buffer = Vec::new();
-        self.reader.read_until(b'\r', &mut buffer)?;
+/// Records a frame into the capture, if any. The capture is a side channel, so if it fails it is
+/// dropped instead of failing the transaction.
+fn record(
+    capture: &mut Option<CaptureWriter<Box<dyn Write + Send>>>,
+    direction: Direction,
+    bytes: &[u8],
+) {
+    let Some(writer) = capture else {
+        return;
+    };
 
-        let string = std::str::from_utf8(&buffer)?;
-        let msg = Message::parse(string)?;
+    let result = writer.write(&Record {
+        direction,
+        timestamp: SystemTime::now(),
+        bytes: bytes.to_vec(),
+    });
+
+    if let Err(_error) = result {
+        debug!(error = %_error, "capture failed, recording stopped");
+        *capture = None;
+    }
+}
 
-        Ok(msg)
+impl Debug for PlcDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlcDevice")
+            .field("node_id", &self.node_id)
+            .field("retries", &self.retries)
+            .field("subscriptions", &self.subscriptions)
+            .field("capturing", &self.capture.is_some())
+            .finish_non_exhaustive()
     }
 }
@@ -0,0 +1,338 @@
+use super::{DeviceError, Error};
+use crate::protocol::{MessageKind, NodeId, ProtocolError, Response};
+use std::{collections::BTreeMap, fmt::Write, io::ErrorKind, sync::Mutex, time::Duration};
+
+/// Upper bounds of the latency histogram's buckets, in milliseconds.
+/// Responses slower than the last bound are counted in an additional overflow bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 12] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000, 2000, 5000];
+
+/// Link statistics, collected by one or more [`PlcDevice`](super::PlcDevice)s.
+///
+/// Statistics are kept per node and [`MessageKind`]. Wrap them in an [`Arc`](std::sync::Arc)
+/// to share them between devices, or with a thread that exports them.
+#[derive(Debug, Default)]
+pub struct LinkStats {
+    counters: Mutex<BTreeMap<(NodeId, MessageKind), Counters>>,
+}
+
+/// Counters for a single node and command kind (or an aggregate of several).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Counters {
+    /// Commands sent, including retries
+    pub requests: u64,
+    /// Commands answered with a valid, successful response
+    pub successes: u64,
+    /// Commands which weren't (completely) answered in time
+    pub timeouts: u64,
+    /// Responses with an FCS mismatch
+    pub fcs_errors: u64,
+    /// Other failures, such as malformed or unexpected responses
+    pub other_errors: u64,
+    /// Commands sent again after a failure
+    pub retries: u64,
+    /// Responses with an end code other than "normal completion", by end code
+    pub end_codes: BTreeMap<DeviceError, u64>,
+    /// Time between sending a command and receiving its response
+    pub latency: Histogram,
+}
+
+/// A latency histogram with the buckets in [`LATENCY_BUCKETS_MS`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    sum: Duration,
+}
+
+/// A counter exported to Prometheus: metric name, help text and how to get its value.
+type Metric = (&'static str, &'static str, fn(&Counters) -> u64);
+
+/// A copy of the statistics at a point in time.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StatsSnapshot {
+    pub entries: BTreeMap<(NodeId, MessageKind), Counters>,
+}
+
+impl LinkStats {
+    /// Creates empty statistics.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the outcome of a single attempt at a transaction.
+    pub(super) fn record(
+        &self,
+        node: NodeId,
+        kind: MessageKind,
+        result: &Result<Response, Error>,
+        latency: Duration,
+    ) {
+        self.update(node, kind, |counters| {
+            counters.requests += 1;
+
+            match result {
+                Ok(response) => {
+                    counters.latency.observe(latency);
+
+                    match response.error() {
+                        Some(error) => *counters.end_codes.entry(error).or_default() += 1,
+                        None => counters.successes += 1,
+                    }
+                }
+                Err(Error::Device(error)) => {
+                    counters.latency.observe(latency);
+                    *counters.end_codes.entry(*error).or_default() += 1;
+                }
+                Err(Error::Io(error)) if error.kind() == ErrorKind::TimedOut => {
+                    counters.timeouts += 1;
+                }
+                Err(Error::Protocol(ProtocolError::FcsMismatch { .. })) => counters.fcs_errors += 1,
+                Err(_) => counters.other_errors += 1,
+            }
+        });
+    }
+
+    /// Records that a transaction is about to be retried.
+    pub(super) fn record_retry(&self, node: NodeId, kind: MessageKind) {
+        self.update(node, kind, |counters| counters.retries += 1);
+    }
+
+    /// Returns a copy of the current statistics.
+    #[must_use]
+    pub fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            entries: self.lock().clone(),
+        }
+    }
+
+    /// Resets all counters.
+    pub fn reset(&self) {
+        self.lock().clear();
+    }
+
+    fn update(&self, node: NodeId, kind: MessageKind, update: impl FnOnce(&mut Counters)) {
+        update(self.lock().entry((node, kind)).or_default());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<(NodeId, MessageKind), Counters>> {
+        self.counters
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Counters {
+    /// Adds another set of counters to this one.
+    pub fn merge(&mut self, other: &Self) {
+        self.requests += other.requests;
+        self.successes += other.successes;
+        self.timeouts += other.timeouts;
+        self.fcs_errors += other.fcs_errors;
+        self.other_errors += other.other_errors;
+        self.retries += other.retries;
+
+        for (code, count) in &other.end_codes {
+            *self.end_codes.entry(*code).or_default() += count;
+        }
+
+        self.latency.merge(&other.latency);
+    }
+
+    /// Returns the number of failed requests (including end codes other than "normal completion").
+    #[must_use]
+    pub fn failures(&self) -> u64 {
+        self.timeouts + self.fcs_errors + self.other_errors + self.end_codes.values().sum::<u64>()
+    }
+}
+
+impl Histogram {
+    /// Adds a measurement.
+    pub fn observe(&mut self, latency: Duration) {
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| latency <= Duration::from_millis(*bound))
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+
+        self.buckets[bucket] += 1;
+        self.sum += latency;
+    }
+
+    /// Returns the number of measurements in each bucket. The last bucket counts measurements above all bounds.
+    #[must_use]
+    pub const fn buckets(&self) -> &[u64] {
+        &self.buckets
+    }
+
+    /// Returns the number of measurements.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Returns the sum of all measurements.
+    #[must_use]
+    pub const fn sum(&self) -> Duration {
+        self.sum
+    }
+
+    /// Returns the upper bound of the bucket containing the `q`-quantile (`0.0..=1.0`).
+    /// Returns `None` if there are no measurements, or the quantile is above the largest bound.
+    #[must_use]
+    pub fn quantile(&self, q: f64) -> Option<Duration> {
+        let count = self.count();
+
+        if count == 0 {
+            return None;
+        }
+
+        #[allow(
+            clippy::cast_precision_loss,
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss
+        )]
+        let rank = ((q.clamp(0.0, 1.0) * count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+
+        LATENCY_BUCKETS_MS
+            .iter()
+            .zip(self.buckets)
+            .find_map(|(bound, in_bucket)| {
+                seen += in_bucket;
+                (seen >= rank).then(|| Duration::from_millis(*bound))
+            })
+    }
+
+    /// Returns the median latency (see [`quantile`](Self::quantile)).
+    #[must_use]
+    pub fn p50(&self) -> Option<Duration> {
+        self.quantile(0.5)
+    }
+
+    /// Returns the 99th percentile latency (see [`quantile`](Self::quantile)).
+    #[must_use]
+    pub fn p99(&self) -> Option<Duration> {
+        self.quantile(0.99)
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket += count;
+        }
+
+        self.sum += other.sum;
+    }
+}
+
+impl StatsSnapshot {
+    /// Returns the counters of all command kinds sent to `node`, combined.
+    #[must_use]
+    pub fn node(&self, node: NodeId) -> Counters {
+        self.entries
+            .iter()
+            .filter(|((entry_node, _), _)| *entry_node == node)
+            .fold(Counters::default(), |mut total, (_, counters)| {
+                total.merge(counters);
+                total
+            })
+    }
+
+    /// Returns the counters of all nodes and command kinds, combined.
+    #[must_use]
+    pub fn total(&self) -> Counters {
+        self.entries
+            .values()
+            .fold(Counters::default(), |mut total, counters| {
+                total.merge(counters);
+                total
+            })
+    }
+
+    /// Renders the statistics in the Prometheus text exposition format.
+    /// Every metric is labeled with `node` and `kind` (the header code).
+    #[must_use]
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::new();
+        let counters: [Metric; 6] = [
+            ("requests", "Commands sent, including retries", |c| {
+                c.requests
+            }),
+            ("successes", "Successful responses", |c| c.successes),
+            ("timeouts", "Commands not answered in time", |c| c.timeouts),
+            ("fcs_errors", "Responses with an FCS mismatch", |c| {
+                c.fcs_errors
+            }),
+            ("other_errors", "Malformed or unexpected responses", |c| {
+                c.other_errors
+            }),
+            ("retries", "Commands sent again after a failure", |c| {
+                c.retries
+            }),
+        ];
+
+        for (name, help, value) in counters {
+            let _ = writeln!(output, "# HELP hostlink_{name}_total {help}");
+            let _ = writeln!(output, "# TYPE hostlink_{name}_total counter");
+
+            for ((node, kind), entry) in &self.entries {
+                let _ = writeln!(
+                    output,
+                    "hostlink_{name}_total{{node=\"{node}\",kind=\"{}\"}} {}",
+                    kind.code(),
+                    value(entry)
+                );
+            }
+        }
+
+        let _ = writeln!(
+            output,
+            "# HELP hostlink_end_codes_total Responses with an error end code"
+        );
+        let _ = writeln!(output, "# TYPE hostlink_end_codes_total counter");
+
+        for ((node, kind), entry) in &self.entries {
+            for (error, count) in &entry.end_codes {
+                let _ = writeln!(
+                    output,
+                    "hostlink_end_codes_total{{node=\"{node}\",kind=\"{}\",code=\"{}\"}} {count}",
+                    kind.code(),
+                    error.code()
+                );
+            }
+        }
+
+        let _ = writeln!(output, "# HELP hostlink_latency_seconds Response latency");
+        let _ = writeln!(output, "# TYPE hostlink_latency_seconds histogram");
+
+        for ((node, kind), entry) in &self.entries {
+            let labels = format!("node=\"{node}\",kind=\"{}\"", kind.code());
+            let mut cumulative = 0;
+
+            for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(entry.latency.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    output,
+                    "hostlink_latency_seconds_bucket{{{labels},le=\"{}\"}} {cumulative}",
+                    Duration::from_millis(*bound).as_secs_f64()
+                );
+            }
+
+            let _ = writeln!(
+                output,
+                "hostlink_latency_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                entry.latency.count()
+            );
+            let _ = writeln!(
+                output,
+                "hostlink_latency_seconds_sum{{{labels}}} {}",
+                entry.latency.sum.as_secs_f64()
+            );
+            let _ = writeln!(
+                output,
+                "hostlink_latency_seconds_count{{{labels}}} {}",
+                entry.latency.count()
+            );
+        }
+
+        output
+    }
+}
@@ -0,0 +1,124 @@
+use super::Error;
+use crate::protocol::Address;
+use derive_more::Display;
+use std::{
+    sync::mpsc::{Receiver, Sender},
+    time::SystemTime,
+};
+
+/// Quality of a subscribed value.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Quality {
+    /// The value was just read from the PLC.
+    Good,
+    /// The link works, but the PLC refused to return the value (it reported an end code).
+    Stale,
+    /// The value could not be read due to a communication failure.
+    CommFail,
+}
+
+/// A value read from a subscribed address.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Value {
+    /// Value of a word address
+    Word(u16),
+    /// Value of a bit address
+    Bit(bool),
+}
+
+/// An event emitted when a subscribed value (or its quality) changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueChange {
+    /// Subscribed address
+    pub address: Address,
+    /// Last known value (if any was read so far)
+    pub value: Option<Value>,
+    /// Previously reported value
+    pub previous: Option<Value>,
+    /// Quality of `value`
+    pub quality: Quality,
+    /// Time of the poll that produced this event
+    pub timestamp: SystemTime,
+}
+
+#[derive(Debug)]
+pub(super) struct Subscription {
+    address: Address,
+    deadband: u16,
+    sender: Sender<ValueChange>,
+    value: Option<Value>,
+    quality: Option<Quality>,
+}
+
+impl Value {
+    fn from_word(address: Address, word: u16) -> Self {
+        match address.bit {
+            Some(bit) => Self::Bit(word & (1 << bit) != 0),
+            None => Self::Word(word),
+        }
+    }
+
+    fn exceeds_deadband(self, previous: Self, deadband: u16) -> bool {
+        match (self, previous) {
+            (Self::Word(new), Self::Word(old)) => new.abs_diff(old) > deadband,
+            _ => self != previous,
+        }
+    }
+}
+
+impl Subscription {
+    pub(super) fn new(address: Address, deadband: u16) -> (Self, Receiver<ValueChange>) {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        (
+            Self {
+                address,
+                deadband,
+                sender,
+                value: None,
+                quality: None,
+            },
+            receiver,
+        )
+    }
+
+    pub(super) const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Processes the result of a poll. Returns `false` if the receiver was dropped.
+    pub(super) fn update(&mut self, result: &Result<u16, Error>, timestamp: SystemTime) -> bool {
+        let previous = self.value;
+
+        let (value, quality) = match result {
+            Ok(word) => (Some(Value::from_word(self.address, *word)), Quality::Good),
+            Err(Error::Device(..)) => (previous, Quality::Stale),
+            Err(..) => (previous, Quality::CommFail),
+        };
+
+        let changed = self.quality != Some(quality)
+            || match (previous, value) {
+                (Some(old), Some(new)) => new.exceeds_deadband(old, self.deadband),
+                (None, new) => new.is_some(),
+                (Some(..), None) => false,
+            };
+
+        if !changed {
+            return true;
+        }
+
+        // only the reported value is kept, so slow drifts accumulate until they exceed the deadband
+        self.value = value;
+        self.quality = Some(quality);
+
+        self.sender
+            .send(ValueChange {
+                address: self.address,
+                value,
+                previous,
+                quality,
+                timestamp,
+            })
+            .is_ok()
+    }
+}
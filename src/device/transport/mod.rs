@@ -0,0 +1,155 @@
+mod rfc2217;
+mod tcp;
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+pub use rfc2217::{Rfc2217Transport, SerialSettings};
+pub use tcp::TcpTransport;
+
+/// A byte stream that a [`PlcDevice`](super::PlcDevice) can communicate over.
+///
+/// This is implemented for every type that can be read from and written to, such as serial ports.
+/// Reads are expected to time out (with [`TimedOut`](io::ErrorKind::TimedOut)) when no data arrives.
+pub trait Transport: Read + Write + Send {}
+
+impl<T: Read + Write + Send + ?Sized> Transport for T {}
+
+/// Something that answers Hostlink frames, such as a simulated PLC.
+pub trait Responder {
+    /// Handles a single frame (including the terminating `\r`) and returns the bytes to send back.
+    fn respond(&mut self, frame: &[u8]) -> Reply;
+}
+
+/// Bytes sent back by a [`Responder`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Reply {
+    /// Bytes to send back (nothing is sent back if this is empty)
+    pub bytes: Vec<u8>,
+    /// Time after which the bytes arrive
+    pub delay: Duration,
+}
+
+impl From<Vec<u8>> for Reply {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+impl<R: Responder + ?Sized> Responder for Box<R> {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        (**self).respond(frame)
+    }
+}
+
+impl<R: Responder + ?Sized> Responder for Arc<Mutex<R>> {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        self.lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .respond(frame)
+    }
+}
+
+/// An in-memory transport which passes every written frame to a [`Responder`].
+///
+/// Delayed replies arrive once their delay has passed. A read which would have to wait longer than
+/// the transport's timeout fails with [`TimedOut`](io::ErrorKind::TimedOut), just like a serial port would.
+/// A reply which arrives too late is dropped, so it isn't taken for the answer to the next command.
+/// If no reply is pending at all, reads fail immediately instead of waiting for the timeout.
+#[derive(Debug)]
+pub struct MemoryTransport<R> {
+    responder: R,
+    timeout: Duration,
+    incoming: Vec<u8>,
+    outgoing: VecDeque<(Instant, VecDeque<u8>)>,
+}
+
+impl<R: Responder> MemoryTransport<R> {
+    /// Creates a transport connected to the specified responder.
+    pub const fn new(responder: R) -> Self {
+        Self {
+            responder,
+            timeout: super::DEFAULT_TIMEOUT,
+            incoming: Vec::new(),
+            outgoing: VecDeque::new(),
+        }
+    }
+
+    /// Sets the read timeout.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns a reference to the responder.
+    pub const fn responder(&self) -> &R {
+        &self.responder
+    }
+
+    /// Returns a mutable reference to the responder.
+    pub fn responder_mut(&mut self) -> &mut R {
+        &mut self.responder
+    }
+}
+
+impl<R: Responder> Write for MemoryTransport<R> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.incoming.extend_from_slice(buf);
+
+        while let Some(end) = self.incoming.iter().position(|byte| *byte == b'\r') {
+            let frame: Vec<u8> = self.incoming.drain(..=end).collect();
+            let reply = self.responder.respond(&frame);
+
+            if !reply.bytes.is_empty() {
+                self.outgoing
+                    .push_back((Instant::now() + reply.delay, reply.bytes.into()));
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<R: Responder> Read for MemoryTransport<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((arrival, bytes)) = self.outgoing.front_mut() else {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no response from responder",
+            ));
+        };
+
+        let wait = arrival.saturating_duration_since(Instant::now());
+
+        if wait > self.timeout {
+            std::thread::sleep(self.timeout);
+            self.outgoing.pop_front();
+
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "response not received in time",
+            ));
+        }
+
+        std::thread::sleep(wait);
+        let count = bytes.read(buf)?;
+
+        if bytes.is_empty() {
+            self.outgoing.pop_front();
+        }
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,316 @@
+use super::tcp::TcpTransport;
+use crate::device::{DataBits, Parity, StopBits};
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+// Telnet commands (RFC 854)
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+// Telnet options
+const BINARY: u8 = 0;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const COM_PORT_OPTION: u8 = 44;
+
+// COM-PORT-OPTION subcommands (RFC 2217). The server's acknowledgements (code + 100) are skipped.
+const SET_BAUDRATE: u8 = 1;
+const SET_DATASIZE: u8 = 2;
+const SET_PARITY: u8 = 3;
+const SET_STOPSIZE: u8 = 4;
+const SET_CONTROL: u8 = 5;
+
+/// Serial settings negotiated with an RFC 2217 server.
+/// The defaults match the PLC's factory settings (9600 baud, 7 data bits, even parity, 2 stop bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialSettings {
+    pub baud: u32,
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+}
+
+impl Default for SerialSettings {
+    fn default() -> Self {
+        Self {
+            baud: 9600,
+            data_bits: DataBits::Seven,
+            parity: Parity::Even,
+            stop_bits: StopBits::Two,
+        }
+    }
+}
+
+impl SerialSettings {
+    /// Returns the subnegotiations which configure the remote serial port.
+    fn commands(&self) -> Vec<u8> {
+        let data_size = match self.data_bits {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8,
+        };
+        let parity = match self.parity {
+            Parity::None => 1,
+            Parity::Odd => 2,
+            Parity::Even => 3,
+        };
+        let stop_size = match self.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+
+        let mut commands = Vec::new();
+        subnegotiate(&mut commands, SET_BAUDRATE, &self.baud.to_be_bytes());
+        subnegotiate(&mut commands, SET_DATASIZE, &[data_size]);
+        subnegotiate(&mut commands, SET_PARITY, &[parity]);
+        subnegotiate(&mut commands, SET_STOPSIZE, &[stop_size]);
+        // no flow control
+        subnegotiate(&mut commands, SET_CONTROL, &[1]);
+        commands
+    }
+}
+
+/// A transport for serial device servers speaking the Telnet COM port control option (RFC 2217).
+///
+/// Unlike [`TcpTransport`], the baud rate, data bits, parity and stop bits of the remote serial port
+/// are set by the client, on every (re)connection. Telnet commands sent by the server are
+/// answered or stripped from the data, and `0xFF` bytes are escaped in both directions.
+#[derive(Debug)]
+pub struct Rfc2217Transport {
+    tcp: TcpTransport,
+    settings: SerialSettings,
+    parser: Parser,
+    /// Data received but not yet read
+    pending: Vec<u8>,
+}
+
+impl Rfc2217Transport {
+    /// Connects to an RFC 2217 server and configures its serial port.
+    /// `timeout` is used for connecting, for the negotiation and for every read.
+    pub fn connect(
+        address: impl ToSocketAddrs,
+        settings: SerialSettings,
+        timeout: Duration,
+    ) -> io::Result<Self> {
+        let mut transport = Self {
+            tcp: TcpTransport::connect(address, timeout)?,
+            settings,
+            parser: Parser::default(),
+            pending: Vec::new(),
+        };
+        negotiate(&mut transport.tcp, &mut transport.parser, settings, timeout)?;
+
+        Ok(transport)
+    }
+
+    /// Enables or disables Nagle's algorithm (disabled by default).
+    pub fn set_nodelay(&mut self, nodelay: bool) -> io::Result<()> {
+        self.tcp.set_nodelay(nodelay)
+    }
+
+    /// Returns the address of the serial device server.
+    #[must_use]
+    pub const fn address(&self) -> SocketAddr {
+        self.tcp.address()
+    }
+
+    /// Returns the negotiated serial settings.
+    #[must_use]
+    pub const fn settings(&self) -> SerialSettings {
+        self.settings
+    }
+}
+
+/// Offers the COM port option, waits for the server to accept it and sends the serial settings.
+fn negotiate(
+    tcp: &mut TcpTransport,
+    parser: &mut Parser,
+    settings: SerialSettings,
+    timeout: Duration,
+) -> io::Result<()> {
+    *parser = Parser::default();
+    tcp.write_raw(&[
+        IAC,
+        WILL,
+        COM_PORT_OPTION,
+        IAC,
+        WILL,
+        BINARY,
+        IAC,
+        DO,
+        BINARY,
+        IAC,
+        DO,
+        SUPPRESS_GO_AHEAD,
+    ])?;
+
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0; 64];
+
+    while parser.com_port.is_none() {
+        if Instant::now() >= deadline {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "server didn't answer the COM port option",
+            ));
+        }
+
+        let count = tcp.receive(&mut buf)?;
+        let mut replies = Vec::new();
+        // data arriving before the negotiation is complete is stale and dropped
+        parser.parse(&buf[..count], &mut Vec::new(), &mut replies);
+        tcp.write_raw(&replies)?;
+    }
+
+    if parser.com_port == Some(false) {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "server refused the COM port option",
+        ));
+    }
+
+    tcp.write_raw(&settings.commands())
+}
+
+impl Read for Rfc2217Transport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if !self.pending.is_empty() {
+                let count = buf.len().min(self.pending.len());
+                buf[..count].copy_from_slice(&self.pending[..count]);
+                self.pending.drain(..count);
+                return Ok(count);
+            }
+
+            let mut raw = vec![0; buf.len()];
+            let count = self.tcp.receive(&mut raw)?;
+            let mut replies = Vec::new();
+            self.parser
+                .parse(&raw[..count], &mut self.pending, &mut replies);
+
+            if !replies.is_empty() {
+                self.tcp.write_raw(&replies)?;
+            }
+        }
+    }
+}
+
+impl Write for Rfc2217Transport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut escaped = Vec::with_capacity(buf.len());
+
+        for byte in buf {
+            if *byte == IAC {
+                escaped.push(IAC);
+            }
+            escaped.push(*byte);
+        }
+
+        let Self {
+            tcp,
+            settings,
+            parser,
+            ..
+        } = self;
+        let timeout = tcp.timeout();
+        tcp.send(&escaped, &mut |tcp| {
+            negotiate(tcp, parser, *settings, timeout)
+        })?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn subnegotiate(output: &mut Vec<u8>, command: u8, value: &[u8]) {
+    output.extend_from_slice(&[IAC, SB, COM_PORT_OPTION, command]);
+
+    for byte in value {
+        if *byte == IAC {
+            output.push(IAC);
+        }
+        output.push(*byte);
+    }
+
+    output.extend_from_slice(&[IAC, SE]);
+}
+
+/// Where the parser is within a Telnet command.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+    #[default]
+    Data,
+    Iac,
+    Option(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+/// A Telnet stream parser, which separates data from commands.
+#[derive(Debug, Default)]
+struct Parser {
+    state: State,
+    /// Whether the server accepted (`DO`) or refused (`DONT`) the COM port option
+    com_port: Option<bool>,
+}
+
+impl Parser {
+    /// Parses received bytes, appending data to `data` and answers to option requests to `replies`.
+    fn parse(&mut self, input: &[u8], data: &mut Vec<u8>, replies: &mut Vec<u8>) {
+        for byte in input {
+            self.state = match (self.state, *byte) {
+                (State::Data, IAC) => State::Iac,
+                (State::Data, byte) => {
+                    data.push(byte);
+                    State::Data
+                }
+                (State::Iac, IAC) => {
+                    data.push(IAC);
+                    State::Data
+                }
+                (State::Iac, SB) => State::Subnegotiation,
+                (State::Iac, command @ (DO | DONT | WILL | WONT)) => State::Option(command),
+                // NOP, GA and other commands without an option
+                (State::Iac, _) => State::Data,
+                (State::Option(command), option) => {
+                    self.option(command, option, replies);
+                    State::Data
+                }
+                // signature and line state notifications aren't needed, so their contents are skipped
+                (State::Subnegotiation, IAC) => State::SubnegotiationIac,
+                (State::Subnegotiation, _) | (State::SubnegotiationIac, IAC) => {
+                    State::Subnegotiation
+                }
+                (State::SubnegotiationIac, _) => State::Data,
+            };
+        }
+    }
+
+    fn option(&mut self, command: u8, option: u8, replies: &mut Vec<u8>) {
+        match (command, option) {
+            (DO, COM_PORT_OPTION) => self.com_port = Some(true),
+            (DONT, COM_PORT_OPTION) => self.com_port = Some(false),
+            // options we offered or requested ourselves
+            (DO | WILL, BINARY | SUPPRESS_GO_AHEAD) | (DONT | WONT, _) => {}
+            // refuse everything else, such as echo
+            (DO, option) => replies.extend_from_slice(&[IAC, WONT, option]),
+            (WILL, option) => replies.extend_from_slice(&[IAC, DONT, option]),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// A transport for serial device servers in raw TCP mode (sometimes called "TCP server" mode),
+/// which pass bytes between a TCP connection and the serial port unchanged.
+///
+/// Nagle's algorithm is disabled, so every frame is sent immediately.
+/// If the server closes the connection, the read fails and the next write reconnects,
+/// so the connection recovers with [`PlcDevice::set_retries`](crate::device::PlcDevice::set_retries).
+#[derive(Debug)]
+pub struct TcpTransport {
+    address: SocketAddr,
+    timeout: Duration,
+    nodelay: bool,
+    stream: Option<TcpStream>,
+}
+
+impl TcpTransport {
+    /// Connects to a serial device server. `timeout` is used for connecting and for every read.
+    pub fn connect(address: impl ToSocketAddrs, timeout: Duration) -> io::Result<Self> {
+        let address = address.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+        })?;
+
+        let mut transport = Self {
+            address,
+            timeout,
+            nodelay: true,
+            stream: None,
+        };
+        transport.stream = Some(transport.open()?);
+
+        Ok(transport)
+    }
+
+    /// Enables or disables Nagle's algorithm (disabled by default).
+    pub fn set_nodelay(&mut self, nodelay: bool) -> io::Result<()> {
+        self.nodelay = nodelay;
+
+        match &self.stream {
+            Some(stream) => stream.set_nodelay(nodelay),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the address of the serial device server.
+    #[must_use]
+    pub const fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns whether the transport is currently connected.
+    /// A closed connection is only noticed when reading from it.
+    #[must_use]
+    pub const fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    pub(super) const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Writes bytes, reconnecting first if the connection was closed.
+    /// `handshake` is called on every new connection before the bytes are written.
+    pub(super) fn send(
+        &mut self,
+        bytes: &[u8],
+        handshake: &mut dyn FnMut(&mut Self) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let mut reconnected = false;
+
+        loop {
+            if self.stream.is_none() {
+                self.stream = Some(self.open()?);
+                reconnected = true;
+
+                if let Err(error) = handshake(self) {
+                    self.stream = None;
+                    return Err(error);
+                }
+            }
+
+            match self.write_raw(bytes) {
+                Err(error) if !reconnected && is_connection_lost(&error) => self.stream = None,
+                result => return result,
+            }
+        }
+    }
+
+    /// Writes bytes to the current connection without reconnecting.
+    pub(super) fn write_raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let stream = self.stream.as_mut().ok_or_else(closed)?;
+
+        stream.write_all(bytes).and_then(|()| stream.flush())
+    }
+
+    /// Reads from the current connection.
+    /// Read timeouts are reported as [`TimedOut`](io::ErrorKind::TimedOut) on every platform.
+    pub(super) fn receive(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let stream = self.stream.as_mut().ok_or_else(closed)?;
+
+        match stream.read(buf) {
+            Ok(0) if !buf.is_empty() => {
+                self.stream = None;
+                Err(closed())
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no data received in time",
+            )),
+            Err(error) if is_connection_lost(&error) => {
+                self.stream = None;
+                Err(error)
+            }
+            result => result,
+        }
+    }
+
+    fn open(&self) -> io::Result<TcpStream> {
+        let stream = TcpStream::connect_timeout(&self.address, self.timeout)?;
+        stream.set_nodelay(self.nodelay)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        Ok(stream)
+    }
+}
+
+impl Read for TcpTransport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.receive(buf)
+    }
+}
+
+impl Write for TcpTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.send(buf, &mut |_| Ok(()))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn closed() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ConnectionAborted,
+        "connection closed by the serial device server",
+    )
+}
+
+fn is_connection_lost(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::NotConnected
+            | io::ErrorKind::UnexpectedEof
+    )
+}
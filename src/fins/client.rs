@@ -0,0 +1,82 @@
+use super::{
+    Clock, ControllerData, FinsArea, FinsCommand, FinsHeader, FinsParseError, FinsResponse, RunMode,
+};
+use crate::device::{Error, PlcDevice};
+use crate::protocol::ProtocolError;
+
+/// Something that FINS commands can be sent to, regardless of how the PLC is attached.
+///
+/// Implemented by [`PlcDevice`] (Host Link `FA` frames), [`FinsUdpClient`](super::FinsUdpClient)
+/// and [`FinsTcpClient`](super::FinsTcpClient).
+pub trait FinsClient {
+    /// Sends a command and returns the response if its end code reports normal completion.
+    fn fins(&mut self, command: &FinsCommand) -> Result<FinsResponse, Error>;
+
+    /// Reads `count` words from `area`, starting at word `address`.
+    fn read_memory(&mut self, area: FinsArea, address: u16, count: u16) -> Result<Vec<u16>, Error> {
+        let response = self.fins(&FinsCommand::MemoryAreaRead {
+            area,
+            address,
+            count,
+        })?;
+
+        parsed(response.words())
+    }
+
+    /// Writes words into `area`, starting at word `address`.
+    fn write_memory(&mut self, area: FinsArea, address: u16, data: &[u16]) -> Result<(), Error> {
+        self.fins(&FinsCommand::MemoryAreaWrite {
+            area,
+            address,
+            data: data.to_vec(),
+        })
+        .map(drop)
+    }
+
+    /// Starts the program in the specified mode.
+    fn run(&mut self, mode: RunMode) -> Result<(), Error> {
+        self.fins(&FinsCommand::Run(mode)).map(drop)
+    }
+
+    /// Stops the program.
+    fn stop(&mut self) -> Result<(), Error> {
+        self.fins(&FinsCommand::Stop).map(drop)
+    }
+
+    /// Reads the CPU unit's model and version.
+    fn read_controller_data(&mut self) -> Result<ControllerData, Error> {
+        parsed(
+            self.fins(&FinsCommand::ControllerDataRead)?
+                .controller_data(),
+        )
+    }
+
+    /// Reads the PLC's clock.
+    fn read_clock(&mut self) -> Result<Clock, Error> {
+        parsed(self.fins(&FinsCommand::ClockRead)?.clock())
+    }
+}
+
+impl FinsClient for PlcDevice {
+    fn fins(&mut self, command: &FinsCommand) -> Result<FinsResponse, Error> {
+        Self::fins(self, command)
+    }
+}
+
+impl<C: FinsClient + ?Sized> FinsClient for Box<C> {
+    fn fins(&mut self, command: &FinsCommand) -> Result<FinsResponse, Error> {
+        (**self).fins(command)
+    }
+}
+
+/// Returns whether `response` is the response to the command sent with `header`.
+/// Responses to earlier commands (e.g. ones that timed out) don't match and are dropped by the caller.
+pub(super) fn matches(response: &FinsResponse, header: FinsHeader, command: &FinsCommand) -> bool {
+    response.header.sid == header.sid
+        && response.header.sa1 == header.da1
+        && response.command == command.code()
+}
+
+fn parsed<T>(result: Result<T, FinsParseError>) -> Result<T, Error> {
+    result.map_err(|error| ProtocolError::FinsParse(error).into())
+}
@@ -0,0 +1,133 @@
+use derive_more::Display;
+
+/// A memory area of CS/CJ-series PLCs, as addressed by FINS memory area commands.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FinsArea {
+    #[display(fmt = "CIO")]
+    Cio,
+    #[display(fmt = "W")]
+    Work,
+    #[display(fmt = "H")]
+    Holding,
+    #[display(fmt = "A")]
+    Auxiliary,
+    #[display(fmt = "D")]
+    Dm,
+    /// Timer and counter present values
+    #[display(fmt = "T/C")]
+    TimerCounter,
+    /// The current bank of the extended data memory
+    #[display(fmt = "E")]
+    Em,
+}
+
+impl FinsArea {
+    /// Returns the memory area code used for word access.
+    #[must_use]
+    pub const fn word_code(self) -> u8 {
+        match self {
+            Self::Cio => 0xB0,
+            Self::Work => 0xB1,
+            Self::Holding => 0xB2,
+            Self::Auxiliary => 0xB3,
+            Self::Dm => 0x82,
+            Self::TimerCounter => 0x89,
+            Self::Em => 0x98,
+        }
+    }
+}
+
+/// The operating mode a PLC is switched to by [`FinsCommand::Run`].
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RunMode {
+    Monitor,
+    Run,
+}
+
+/// A FINS command.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FinsCommand {
+    /// Reads `count` words, starting at word `address` (MEMORY AREA READ, `0101`).
+    MemoryAreaRead {
+        area: FinsArea,
+        address: u16,
+        count: u16,
+    },
+    /// Writes words, starting at word `address` (MEMORY AREA WRITE, `0102`).
+    MemoryAreaWrite {
+        area: FinsArea,
+        address: u16,
+        data: Vec<u16>,
+    },
+    /// Starts the program in the specified mode (RUN, `0401`).
+    Run(RunMode),
+    /// Stops the program, switching to PROGRAM mode (STOP, `0402`).
+    Stop,
+    /// Reads the CPU unit's model and version (CONTROLLER DATA READ, `0501`).
+    ControllerDataRead,
+    /// Reads the PLC's clock (CLOCK READ, `0701`).
+    ClockRead,
+}
+
+/// Program number which addresses the whole program in RUN and STOP commands.
+const ALL_PROGRAMS: [u8; 2] = [0xFF, 0xFF];
+
+impl FinsCommand {
+    /// Returns the command code (MRC and SRC).
+    #[must_use]
+    pub const fn code(&self) -> u16 {
+        match self {
+            Self::MemoryAreaRead { .. } => 0x0101,
+            Self::MemoryAreaWrite { .. } => 0x0102,
+            Self::Run(..) => 0x0401,
+            Self::Stop => 0x0402,
+            Self::ControllerDataRead => 0x0501,
+            Self::ClockRead => 0x0701,
+        }
+    }
+
+    /// Encodes the command code and parameters.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = self.code().to_be_bytes().to_vec();
+
+        match self {
+            Self::MemoryAreaRead {
+                area,
+                address,
+                count,
+            } => {
+                bytes.push(area.word_code());
+                bytes.extend_from_slice(&address.to_be_bytes());
+                bytes.push(0);
+                bytes.extend_from_slice(&count.to_be_bytes());
+            }
+            Self::MemoryAreaWrite {
+                area,
+                address,
+                data,
+            } => {
+                bytes.push(area.word_code());
+                bytes.extend_from_slice(&address.to_be_bytes());
+                bytes.push(0);
+                // checked by `to_frame` and `to_hostlink`
+                let count = u16::try_from(data.len()).unwrap_or(u16::MAX);
+                bytes.extend_from_slice(&count.to_be_bytes());
+                bytes.extend(data.iter().flat_map(|word| word.to_be_bytes()));
+            }
+            Self::Run(mode) => {
+                bytes.extend_from_slice(&ALL_PROGRAMS);
+                bytes.push(match mode {
+                    RunMode::Monitor => 0x02,
+                    RunMode::Run => 0x04,
+                });
+            }
+            Self::Stop => bytes.extend_from_slice(&ALL_PROGRAMS),
+            // only the model and version are requested
+            Self::ControllerDataRead => bytes.push(0x00),
+            Self::ClockRead => {}
+        }
+
+        bytes
+    }
+}
@@ -0,0 +1,73 @@
+use super::{FinsCommand, FinsHeader, FinsParseError, FinsResponse};
+use crate::protocol::{MessageKind, MessageParams, NodeId, ProtocolError, Request, Response};
+use std::fmt::Write;
+
+/// Maximum length of a Host Link frame, including the terminator.
+const MAX_HOSTLINK_FRAME_LENGTH: usize = 131;
+
+impl FinsCommand {
+    /// Wraps the command into a Host Link [`Fins`](MessageKind::Fins) (`FA`) command.
+    ///
+    /// The parameters are the response wait time (always 0), the header's ICF, DA2, SA2 and SID,
+    /// followed by the command code and parameters, all as hexadecimal digits.
+    ///
+    /// Fails if no words are read or written, or if the command or its response wouldn't fit into
+    /// a single frame (26 words read or 24 written at once).
+    pub fn to_hostlink(&self, node: NodeId, header: FinsHeader) -> Result<Request, ProtocolError> {
+        // `@`, node, header code, end code, ICF, DA2, SA2, SID, FCS and terminator
+        self.check_words(MAX_HOSTLINK_FRAME_LENGTH, |length| 19 + 2 * length)?;
+
+        let mut params = String::from("0");
+        let bytes = [header.icf, header.da2, header.sa2, header.sid]
+            .into_iter()
+            .chain(self.encode());
+
+        for byte in bytes {
+            let _ = write!(params, "{byte:02X}");
+        }
+
+        Ok(Request::new(
+            node,
+            MessageKind::Fins,
+            MessageParams::from_slice(params.as_bytes())?,
+        ))
+    }
+}
+
+impl FinsResponse {
+    /// Unwraps a FINS response from a Host Link [`Fins`](MessageKind::Fins) (`FA`) response.
+    /// The Host Link end code isn't checked.
+    pub fn from_hostlink(response: &Response) -> Result<Self, FinsParseError> {
+        let digits = response.data();
+
+        if !digits.len().is_multiple_of(2) {
+            return Err(FinsParseError::OddLength(digits.len()));
+        }
+
+        let bytes = digits
+            .chunks(2)
+            .map(|pair| {
+                pair.iter()
+                    .map(|&byte| char::from(byte))
+                    .try_fold(0u8, |byte, ch| {
+                        let digit = ch.to_digit(16).ok_or(FinsParseError::InvalidDigit(ch))?;
+
+                        #[allow(clippy::cast_possible_truncation)]
+                        Ok((byte << 4) | digit as u8)
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let [icf, da2, sa2, sid, body @ ..] = &bytes[..] else {
+            return Err(FinsParseError::Truncated);
+        };
+        let header = FinsHeader {
+            icf: *icf,
+            da2: *da2,
+            sa2: *sa2,
+            ..FinsHeader::new(*sid)
+        };
+
+        Self::decode(header, body)
+    }
+}
@@ -0,0 +1,379 @@
+mod client;
+mod command;
+mod hostlink;
+mod tcp;
+mod udp;
+
+use crate::protocol::ProtocolError;
+use std::fmt::Display;
+use thiserror::Error;
+
+pub use client::FinsClient;
+pub use command::{FinsArea, FinsCommand, RunMode};
+pub use tcp::{FinsTcpClient, FINS_TCP_PORT};
+pub use udp::{FinsUdpClient, FINS_UDP_PORT};
+
+/// Bits of the end code which flag a network relay error or a CPU unit error,
+/// without the command itself having failed.
+const END_CODE_FLAGS: u16 = 0x80C0;
+
+/// The ICF bit which marks a frame as a response.
+const ICF_RESPONSE: u8 = 0x40;
+
+/// The longest FINS frame an Ethernet unit sends or accepts, including the header.
+const MAX_FRAME_LENGTH: usize = 2012;
+
+/// A FINS header.
+///
+/// Over Host Link, only the ICF, DA2, SA2 and SID fields are sent; the network and node addresses
+/// are used by [`FinsUdpClient`] and [`FinsTcpClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FinsHeader {
+    /// Information control field
+    pub icf: u8,
+    /// Gateway count (number of bridges a frame may pass)
+    pub gct: u8,
+    /// Destination network address (0 for the local network)
+    pub dna: u8,
+    /// Destination node address
+    pub da1: u8,
+    /// Destination unit address (0 for the CPU unit)
+    pub da2: u8,
+    /// Source network address (0 for the local network)
+    pub sna: u8,
+    /// Source node address
+    pub sa1: u8,
+    /// Source unit address (0 for the CPU unit)
+    pub sa2: u8,
+    /// Service ID, echoed back in the response
+    pub sid: u8,
+}
+
+/// A FINS network address: network, node and unit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FinsAddress {
+    pub network: u8,
+    pub node: u8,
+    /// Unit address (0 for the CPU unit)
+    pub unit: u8,
+}
+
+/// A FINS end code (main and sub response code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Error)]
+#[error("end code {code:04X} ({description})", code = self.0, description = self.description())]
+pub struct EndCode(pub u16);
+
+/// An error that can occur while trying to parse a FINS response.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum FinsParseError {
+    /// The response is shorter than its header, command code and end code
+    #[error("Response is truncated")]
+    Truncated,
+    /// The response isn't marked as a response
+    #[error("Not a response (ICF {0:02X})")]
+    NotAResponse(u8),
+    /// Hexadecimal data has an odd number of digits
+    #[error("Odd number of hexadecimal digits: {0}")]
+    OddLength(usize),
+    /// Data contains a non-hexadecimal character
+    #[error("Invalid hexadecimal digit: '{0}'")]
+    InvalidDigit(char),
+    /// The response data doesn't have the length expected for the command
+    #[error("Unexpected data length: {0}")]
+    BadLength(usize),
+    /// A clock field isn't a valid BCD number
+    #[error("Invalid BCD value: {0:02X}")]
+    InvalidBcd(u8),
+}
+
+/// A FINS response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FinsResponse {
+    pub header: FinsHeader,
+    /// Code of the command this responds to
+    pub command: u16,
+    pub end_code: EndCode,
+    /// Response data following the end code
+    pub data: Vec<u8>,
+}
+
+/// The PLC's clock, as returned by [`FinsCommand::ClockRead`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Clock {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    /// Day of the week, 0 (Sunday) to 6 (Saturday)
+    pub day_of_week: u8,
+}
+
+/// CPU unit information, as returned by [`FinsCommand::ControllerDataRead`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ControllerData {
+    /// Model, such as `CJ2M-CPU31`
+    pub model: String,
+    /// Unit version
+    pub version: String,
+}
+
+impl FinsHeader {
+    /// Length of an encoded header.
+    pub const LENGTH: usize = 10;
+
+    /// Creates a command header addressed to the CPU unit of the local node.
+    #[must_use]
+    pub const fn new(sid: u8) -> Self {
+        Self {
+            icf: 0,
+            gct: 0x02,
+            dna: 0,
+            da1: 0,
+            da2: 0,
+            sna: 0,
+            sa1: 0,
+            sa2: 0,
+            sid,
+        }
+    }
+
+    /// Creates a command header sent from `source` to `destination`.
+    #[must_use]
+    pub const fn addressed(destination: FinsAddress, source: FinsAddress, sid: u8) -> Self {
+        Self {
+            dna: destination.network,
+            da1: destination.node,
+            da2: destination.unit,
+            sna: source.network,
+            sa1: source.node,
+            sa2: source.unit,
+            ..Self::new(sid)
+        }
+    }
+
+    /// Encodes the header as sent over Ethernet.
+    #[must_use]
+    pub const fn encode(self) -> [u8; Self::LENGTH] {
+        [
+            self.icf, 0, self.gct, self.dna, self.da1, self.da2, self.sna, self.sa1, self.sa2,
+            self.sid,
+        ]
+    }
+
+    /// Decodes a header as sent over Ethernet.
+    pub fn decode(bytes: &[u8]) -> Result<Self, FinsParseError> {
+        let [icf, _, gct, dna, da1, da2, sna, sa1, sa2, sid, ..] = *bytes else {
+            return Err(FinsParseError::Truncated);
+        };
+
+        Ok(Self {
+            icf,
+            gct,
+            dna,
+            da1,
+            da2,
+            sna,
+            sa1,
+            sa2,
+            sid,
+        })
+    }
+}
+
+impl Default for FinsHeader {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl FinsCommand {
+    /// Encodes the command with a header, as sent over Ethernet.
+    ///
+    /// Fails if no words or more than fit into a frame (999 words) are read or written.
+    pub fn to_frame(&self, header: FinsHeader) -> Result<Vec<u8>, ProtocolError> {
+        self.check_words(MAX_FRAME_LENGTH, |length| FinsHeader::LENGTH + length)?;
+
+        Ok([&header.encode()[..], &self.encode()].concat())
+    }
+
+    /// Fails if no words or more than fit into a single frame are read or written.
+    /// `frame_length` returns the length of a frame carrying a command or response of `length` bytes.
+    fn check_words(
+        &self,
+        max_length: usize,
+        frame_length: impl Fn(usize) -> usize,
+    ) -> Result<(), ProtocolError> {
+        // the command code, area, address and count come first; the response has an end code
+        let (count, command, response) = match self {
+            Self::MemoryAreaRead { count, .. } => {
+                let count = usize::from(*count);
+                (count, 8, 4 + 2 * count)
+            }
+            Self::MemoryAreaWrite { data, .. } => (data.len(), 8 + 2 * data.len(), 4),
+            _ => return Ok(()),
+        };
+
+        if count == 0 || frame_length(command.max(response)) > max_length {
+            return Err(ProtocolError::InvalidCount(count));
+        }
+
+        Ok(())
+    }
+}
+
+impl EndCode {
+    /// Normal completion.
+    pub const OK: Self = Self(0);
+
+    /// Returns the end code without the network relay and CPU unit error flags.
+    #[must_use]
+    pub const fn code(self) -> u16 {
+        self.0 & !END_CODE_FLAGS
+    }
+
+    /// Returns whether the command completed normally.
+    /// A non-fatal CPU unit error reported alongside doesn't count as a failure.
+    #[must_use]
+    pub const fn is_ok(self) -> bool {
+        self.code() == 0
+    }
+
+    /// Returns whether the PLC reported a fatal CPU unit error.
+    #[must_use]
+    pub const fn is_fatal_error(self) -> bool {
+        self.0 & 0x0080 != 0
+    }
+
+    /// Returns a description of the main response code.
+    #[must_use]
+    pub const fn description(self) -> &'static str {
+        match self.code() >> 8 {
+            0x00 => "normal completion",
+            0x01 => "local node error",
+            0x02 => "destination node error",
+            0x03 => "controller error",
+            0x04 => "service unsupported",
+            0x05 => "routing table error",
+            0x10 => "command format error",
+            0x11 => "parameter error",
+            0x20 => "read not possible",
+            0x21 => "write not possible",
+            0x22 => "not executable in current mode",
+            0x23 => "no such device",
+            0x24 => "cannot start/stop",
+            0x25 => "unit error",
+            0x26 => "command error",
+            0x30 => "access right error",
+            0x40 => "abort",
+            _ => "unknown error",
+        }
+    }
+
+    pub const fn to_result(self) -> Result<(), Self> {
+        if self.is_ok() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl FinsResponse {
+    /// Parses a response from its header and body (command code, end code and data).
+    pub fn decode(header: FinsHeader, body: &[u8]) -> Result<Self, FinsParseError> {
+        if header.icf & ICF_RESPONSE == 0 {
+            return Err(FinsParseError::NotAResponse(header.icf));
+        }
+
+        let [mrc, src, mres, sres, data @ ..] = body else {
+            return Err(FinsParseError::Truncated);
+        };
+
+        Ok(Self {
+            header,
+            command: u16::from_be_bytes([*mrc, *src]),
+            end_code: EndCode(u16::from_be_bytes([*mres, *sres])),
+            data: data.to_vec(),
+        })
+    }
+
+    /// Parses a response frame (header, command code, end code and data), as sent over Ethernet.
+    pub fn parse(frame: &[u8]) -> Result<Self, FinsParseError> {
+        let header = FinsHeader::decode(frame)?;
+
+        Self::decode(header, &frame[FinsHeader::LENGTH..])
+    }
+
+    /// Returns the words read by [`FinsCommand::MemoryAreaRead`].
+    pub fn words(&self) -> Result<Vec<u16>, FinsParseError> {
+        if !self.data.len().is_multiple_of(2) {
+            return Err(FinsParseError::BadLength(self.data.len()));
+        }
+
+        Ok(self
+            .data
+            .chunks(2)
+            .map(|word| u16::from_be_bytes([word[0], word[1]]))
+            .collect())
+    }
+
+    /// Returns the clock read by [`FinsCommand::ClockRead`].
+    pub fn clock(&self) -> Result<Clock, FinsParseError> {
+        let [year, month, day, hour, minute, second, day_of_week] = self.data[..] else {
+            return Err(FinsParseError::BadLength(self.data.len()));
+        };
+        let year = bcd(year)?;
+
+        Ok(Clock {
+            // two-digit years from 98 on are in the 1900s, like the PLC's calendar
+            year: u16::from(year) + if year < 98 { 2000 } else { 1900 },
+            month: bcd(month)?,
+            day: bcd(day)?,
+            hour: bcd(hour)?,
+            minute: bcd(minute)?,
+            second: bcd(second)?,
+            day_of_week: bcd(day_of_week)?,
+        })
+    }
+
+    /// Returns the CPU unit information read by [`FinsCommand::ControllerDataRead`].
+    pub fn controller_data(&self) -> Result<ControllerData, FinsParseError> {
+        let text = |range: std::ops::Range<usize>| {
+            self.data
+                .get(range)
+                .map(|bytes| {
+                    String::from_utf8_lossy(bytes)
+                        .trim_end_matches(['\0', ' '])
+                        .to_string()
+                })
+                .ok_or(FinsParseError::BadLength(self.data.len()))
+        };
+
+        Ok(ControllerData {
+            model: text(0..20)?,
+            version: text(20..40)?,
+        })
+    }
+}
+
+impl Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+fn bcd(value: u8) -> Result<u8, FinsParseError> {
+    let (high, low) = (value >> 4, value & 0x0F);
+
+    if high > 9 || low > 9 {
+        return Err(FinsParseError::InvalidBcd(value));
+    }
+
+    Ok(high * 10 + low)
+}
@@ -0,0 +1,173 @@
+use super::{
+    client, FinsAddress, FinsClient, FinsCommand, FinsHeader, FinsResponse, MAX_FRAME_LENGTH,
+};
+use crate::device::Error;
+use crate::protocol::ProtocolError;
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// The default FINS/TCP port.
+pub const FINS_TCP_PORT: u16 = 9600;
+
+/// Magic bytes at the start of every FINS/TCP frame.
+const MAGIC: [u8; 4] = *b"FINS";
+
+// FINS/TCP frame commands
+const NODE_ADDRESS_REQUEST: u32 = 0;
+const NODE_ADDRESS_RESPONSE: u32 = 1;
+const FRAME_SEND: u32 = 2;
+const FRAME_SEND_ERROR: u32 = 3;
+
+/// Node address that asks the server to assign one.
+const AUTOMATIC_NODE: u32 = 0;
+
+/// A FINS/TCP client, for PLCs with an Ethernet unit (or built-in Ethernet port).
+///
+/// Connecting performs the node address handshake, in which the server assigns the client's
+/// node address and reports its own. Nagle's algorithm is disabled.
+#[derive(Debug)]
+pub struct FinsTcpClient {
+    stream: TcpStream,
+    destination: FinsAddress,
+    source: FinsAddress,
+    sid: u8,
+}
+
+impl FinsTcpClient {
+    /// Connects to the PLC at `address` (usually port [`FINS_TCP_PORT`]) and exchanges node addresses.
+    /// `timeout` is used for connecting and for every read.
+    pub fn connect(address: impl ToSocketAddrs, timeout: Duration) -> io::Result<Self> {
+        let address: SocketAddr = address.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+        })?;
+
+        let stream = TcpStream::connect_timeout(&address, timeout)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+
+        let mut client = Self {
+            stream,
+            destination: FinsAddress::default(),
+            source: FinsAddress::default(),
+            sid: 0,
+        };
+
+        client.send(NODE_ADDRESS_REQUEST, &AUTOMATIC_NODE.to_be_bytes())?;
+        let (command, data) = client.receive()?;
+
+        // both node addresses are sent as 4 bytes, of which only the last is used
+        let (NODE_ADDRESS_RESPONSE, [_, _, _, client_node, _, _, _, server_node, ..]) =
+            (command, &data[..])
+        else {
+            return Err(invalid("unexpected response to the node address request"));
+        };
+
+        let node = |node: u8| FinsAddress {
+            node,
+            ..FinsAddress::default()
+        };
+        client.source = node(*client_node);
+        client.destination = node(*server_node);
+
+        Ok(client)
+    }
+
+    /// Returns the PLC's FINS address, as reported by the server.
+    #[must_use]
+    pub const fn destination(&self) -> FinsAddress {
+        self.destination
+    }
+
+    /// Returns the client's FINS address, as assigned by the server.
+    #[must_use]
+    pub const fn source(&self) -> FinsAddress {
+        self.source
+    }
+
+    /// Sends a FINS/TCP frame.
+    fn send(&mut self, command: u32, data: &[u8]) -> io::Result<()> {
+        #[allow(clippy::cast_possible_truncation)]
+        let length = (8 + data.len()) as u32;
+
+        let mut frame = Vec::with_capacity(16 + data.len());
+        frame.extend_from_slice(&MAGIC);
+        frame.extend_from_slice(&length.to_be_bytes());
+        frame.extend_from_slice(&command.to_be_bytes());
+        // error code
+        frame.extend_from_slice(&[0; 4]);
+        frame.extend_from_slice(data);
+
+        self.stream.write_all(&frame)?;
+        self.stream.flush()
+    }
+
+    /// Receives a FINS/TCP frame, returning its command and data.
+    /// Frames carrying an error code are returned as errors.
+    fn receive(&mut self) -> io::Result<(u32, Vec<u8>)> {
+        let mut header = [0; 16];
+        self.stream.read_exact(&mut header)?;
+
+        let field = |index: usize| {
+            u32::from_be_bytes([
+                header[index],
+                header[index + 1],
+                header[index + 2],
+                header[index + 3],
+            ])
+        };
+
+        if header[..4] != MAGIC {
+            return Err(invalid("not a FINS/TCP frame"));
+        }
+
+        let (length, command, error) = (field(4), field(8), field(12));
+        // the length covers the command and error code fields before the data
+        let length = (length as usize).saturating_sub(8);
+
+        if length > MAX_FRAME_LENGTH {
+            return Err(invalid(format!(
+                "FINS/TCP frame of {length} bytes is too long"
+            )));
+        }
+
+        let mut data = vec![0; length];
+        self.stream.read_exact(&mut data)?;
+
+        if error != 0 || command == FRAME_SEND_ERROR {
+            return Err(invalid(format!("FINS/TCP error code {error:08X}")));
+        }
+
+        Ok((command, data))
+    }
+}
+
+impl FinsClient for FinsTcpClient {
+    fn fins(&mut self, command: &FinsCommand) -> Result<FinsResponse, Error> {
+        self.sid = self.sid.wrapping_add(1);
+        let header = FinsHeader::addressed(self.destination, self.source, self.sid);
+        self.send(FRAME_SEND, &command.to_frame(header)?)?;
+
+        loop {
+            let (kind, data) = self.receive()?;
+
+            if kind != FRAME_SEND {
+                continue;
+            }
+
+            let response = FinsResponse::parse(&data).map_err(ProtocolError::FinsParse)?;
+
+            if client::matches(&response, header, command) {
+                response.end_code.to_result()?;
+                return Ok(response);
+            }
+        }
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
@@ -0,0 +1,119 @@
+use super::{
+    client, FinsAddress, FinsClient, FinsCommand, FinsHeader, FinsResponse, MAX_FRAME_LENGTH,
+};
+use crate::device::Error;
+use std::{
+    io,
+    net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
+};
+
+/// The default FINS/UDP port.
+pub const FINS_UDP_PORT: u16 = 9600;
+
+/// A FINS/UDP client, for PLCs with an Ethernet unit (or built-in Ethernet port).
+///
+/// Node addresses default to the last octet of the IP addresses, like the Ethernet unit's
+/// automatic address generation. Datagrams which aren't the response to the pending command
+/// (e.g. late responses to earlier commands, or anything that isn't a FINS response) are dropped.
+#[derive(Debug)]
+pub struct FinsUdpClient {
+    socket: UdpSocket,
+    timeout: Duration,
+    destination: FinsAddress,
+    source: FinsAddress,
+    sid: u8,
+}
+
+impl FinsUdpClient {
+    /// Creates a client sending to the PLC at `address` (usually port [`FINS_UDP_PORT`]).
+    /// `timeout` is the time to wait for each response.
+    pub fn connect(address: impl ToSocketAddrs, timeout: Duration) -> io::Result<Self> {
+        let address = address.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+        })?;
+        let local: SocketAddr = match address {
+            SocketAddr::V4(..) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(..) => ([0; 16], 0).into(),
+        };
+
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(address)?;
+        socket.set_read_timeout(Some(timeout))?;
+
+        let node = |ip: IpAddr| FinsAddress {
+            node: match ip {
+                IpAddr::V4(ip) => ip.octets()[3],
+                IpAddr::V6(..) => 0,
+            },
+            ..FinsAddress::default()
+        };
+
+        Ok(Self {
+            destination: node(address.ip()),
+            source: node(socket.local_addr()?.ip()),
+            socket,
+            timeout,
+            sid: 0,
+        })
+    }
+
+    /// Returns the FINS address commands are sent to.
+    #[must_use]
+    pub const fn destination(&self) -> FinsAddress {
+        self.destination
+    }
+
+    /// Sets the FINS address commands are sent to.
+    pub fn set_destination(&mut self, destination: FinsAddress) {
+        self.destination = destination;
+    }
+
+    /// Returns the FINS address commands are sent from.
+    #[must_use]
+    pub const fn source(&self) -> FinsAddress {
+        self.source
+    }
+
+    /// Sets the FINS address commands are sent from.
+    pub fn set_source(&mut self, source: FinsAddress) {
+        self.source = source;
+    }
+}
+
+impl FinsClient for FinsUdpClient {
+    fn fins(&mut self, command: &FinsCommand) -> Result<FinsResponse, Error> {
+        self.sid = self.sid.wrapping_add(1);
+        let header = FinsHeader::addressed(self.destination, self.source, self.sid);
+        self.socket.send(&command.to_frame(header)?)?;
+
+        let deadline = Instant::now() + self.timeout;
+        let mut buffer = [0; MAX_FRAME_LENGTH];
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            if remaining.is_zero() {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "no FINS response").into());
+            }
+
+            self.socket.set_read_timeout(Some(remaining))?;
+            let count = self
+                .socket
+                .recv(&mut buffer)
+                .map_err(|error| match error.kind() {
+                    io::ErrorKind::WouldBlock => io::ErrorKind::TimedOut.into(),
+                    _ => error,
+                })?;
+
+            let Ok(response) = FinsResponse::parse(&buffer[..count]) else {
+                continue;
+            };
+
+            if client::matches(&response, header, command) {
+                response.end_code.to_result()?;
+                return Ok(response);
+            }
+        }
+    }
+}
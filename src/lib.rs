@@ -1,7 +1,41 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::module_name_repetitions, clippy::missing_errors_doc)]
 
+#[cfg(feature = "std")]
+#[macro_use]
+mod trace;
+
+/// Passive analysis of traffic captured on a Hostlink line.
+#[cfg(feature = "std")]
+pub mod analyzer;
+
+/// Recording and replaying of Hostlink traffic.
+#[cfg(feature = "std")]
+pub mod capture;
+
+/// Shared parts of the `hostlink` and `hostlink-exporter` command-line tools.
+#[cfg(feature = "cli")]
+pub mod cli;
+
 /// Module for communicating with PLCs using Hostlink.
+#[cfg(feature = "std")]
 pub mod device;
 
+/// FINS commands for CS/CJ-series PLCs.
+#[cfg(feature = "std")]
+pub mod fins;
+
+/// A Modbus TCP to Hostlink gateway.
+#[cfg(feature = "std")]
+pub mod modbus;
+
 /// Contains implementations of the Hostlink protocol.
 pub mod protocol;
+
+/// A library for building the slave side of Hostlink links (PLC emulators).
+#[cfg(feature = "std")]
+pub mod server;
+
+/// An in-process PLC simulator implementing the Hostlink slave side.
+#[cfg(feature = "std")]
+pub mod sim;
@@ -0,0 +1,140 @@
+use crate::protocol::Address;
+
+/// A range of Modbus addresses mapped onto consecutive Hostlink words or bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range {
+    /// First Modbus address of the range
+    pub start: u16,
+    /// Number of registers or bits in the range
+    pub count: u16,
+    /// Hostlink word (for registers) or bit (for coils and discrete inputs) that `start` is mapped onto.
+    /// Bit ranges continue into the following words.
+    pub target: Address,
+}
+
+/// The Modbus tables that can be mapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Table {
+    Coils,
+    DiscreteInputs,
+    HoldingRegisters,
+    InputRegisters,
+}
+
+/// Maps Modbus tables onto Hostlink areas.
+/// # Example
+/// ```rust
+/// use hostlink::{modbus::Map, protocol::{Address, Area}};
+///
+/// // Holding registers 0..1000 are DM0000..DM0999, coils 0..160 are IR000.00..IR009.15
+/// let map = Map::new()
+///     .holding_registers(0, 1000, Address::word(Area::Dm, 0))
+///     .coils(0, 160, Address::bit(Area::IrSr, 0, 0).unwrap());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Map {
+    coils: Vec<Range>,
+    discrete_inputs: Vec<Range>,
+    holding_registers: Vec<Range>,
+    input_registers: Vec<Range>,
+}
+
+impl Map {
+    /// Creates an empty map, in which every Modbus address is invalid.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            coils: Vec::new(),
+            discrete_inputs: Vec::new(),
+            holding_registers: Vec::new(),
+            input_registers: Vec::new(),
+        }
+    }
+
+    /// Maps `count` coils starting at `start` onto bits starting at `target`.
+    #[must_use]
+    pub fn coils(self, start: u16, count: u16, target: Address) -> Self {
+        self.with(Table::Coils, start, count, target)
+    }
+
+    /// Maps `count` discrete inputs starting at `start` onto bits starting at `target`.
+    #[must_use]
+    pub fn discrete_inputs(self, start: u16, count: u16, target: Address) -> Self {
+        self.with(Table::DiscreteInputs, start, count, target)
+    }
+
+    /// Maps `count` holding registers starting at `start` onto words starting at `target`.
+    #[must_use]
+    pub fn holding_registers(self, start: u16, count: u16, target: Address) -> Self {
+        self.with(Table::HoldingRegisters, start, count, target)
+    }
+
+    /// Maps `count` input registers starting at `start` onto words starting at `target`.
+    #[must_use]
+    pub fn input_registers(self, start: u16, count: u16, target: Address) -> Self {
+        self.with(Table::InputRegisters, start, count, target)
+    }
+
+    /// Adds a range to a table.
+    #[must_use]
+    pub fn with(mut self, table: Table, start: u16, count: u16, target: Address) -> Self {
+        self.table_mut(table).push(Range {
+            start,
+            count,
+            target,
+        });
+        self
+    }
+
+    /// Returns the Hostlink address of the Modbus address `start`,
+    /// if `count` addresses starting at `start` all lie within the same range
+    /// and are mapped onto words that exist (i.e. don't run past word 65535).
+    #[must_use]
+    pub fn resolve(&self, table: Table, start: u16, count: u16) -> Option<Address> {
+        let range = self.table(table).iter().find(|range| {
+            start >= range.start
+                && u32::from(start) + u32::from(count)
+                    <= u32::from(range.start) + u32::from(range.count)
+        })?;
+        let offset = u32::from(start - range.start);
+        let last = u32::from(count.saturating_sub(1));
+        let word = |index: u32| u16::try_from(u32::from(range.target.word) + index).ok();
+
+        match table {
+            Table::Coils | Table::DiscreteInputs => {
+                let bit = u32::from(range.target.bit.unwrap_or_default()) + offset;
+                word((bit + last) / 16)?;
+
+                #[allow(clippy::cast_possible_truncation)]
+                Some(Address {
+                    area: range.target.area,
+                    word: word(bit / 16)?,
+                    bit: Some((bit % 16) as u8),
+                })
+            }
+            Table::HoldingRegisters | Table::InputRegisters => {
+                word(offset + last)?;
+
+                Some(Address::word(range.target.area, word(offset)?))
+            }
+        }
+    }
+
+    fn table(&self, table: Table) -> &[Range] {
+        match table {
+            Table::Coils => &self.coils,
+            Table::DiscreteInputs => &self.discrete_inputs,
+            Table::HoldingRegisters => &self.holding_registers,
+            Table::InputRegisters => &self.input_registers,
+        }
+    }
+
+    fn table_mut(&mut self, table: Table) -> &mut Vec<Range> {
+        match table {
+            Table::Coils => &mut self.coils,
+            Table::DiscreteInputs => &mut self.discrete_inputs,
+            Table::HoldingRegisters => &mut self.holding_registers,
+            Table::InputRegisters => &mut self.input_registers,
+        }
+    }
+}
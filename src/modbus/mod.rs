@@ -0,0 +1,357 @@
+mod map;
+
+use crate::device::{DeviceError, Error, PlcDevice};
+use crate::protocol::{Address, ProtocolError};
+use derive_more::Display;
+use std::{
+    io::{self, Read, Write},
+    net::TcpListener,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+pub use map::{Map, Range, Table};
+
+/// Largest number of bits in a single read request.
+const MAX_READ_BITS: u16 = 2000;
+/// Largest number of registers in a single read request.
+const MAX_READ_REGISTERS: u16 = 125;
+/// Largest number of bits in a single write request.
+const MAX_WRITE_BITS: u16 = 1968;
+/// Largest number of registers in a single write request.
+const MAX_WRITE_REGISTERS: u16 = 123;
+
+/// A Modbus exception code.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Exception {
+    #[display(fmt = "Illegal function")]
+    IllegalFunction = 0x01,
+    #[display(fmt = "Illegal data address")]
+    IllegalDataAddress = 0x02,
+    #[display(fmt = "Illegal data value")]
+    IllegalDataValue = 0x03,
+    #[display(fmt = "Server device failure")]
+    ServerDeviceFailure = 0x04,
+    #[display(fmt = "Server device busy")]
+    ServerDeviceBusy = 0x06,
+    #[display(fmt = "Gateway target device failed to respond")]
+    GatewayTargetFailedToRespond = 0x0B,
+}
+
+impl From<DeviceError> for Exception {
+    fn from(error: DeviceError) -> Self {
+        match error {
+            DeviceError::AddressOver => Self::IllegalDataAddress,
+            DeviceError::EntryNumberData | DeviceError::IllegalEntryNumber => {
+                Self::IllegalDataValue
+            }
+            DeviceError::InstructionNotFound | DeviceError::UnsupportedCommand(..) => {
+                Self::IllegalFunction
+            }
+            DeviceError::NotExecutableInRunMode
+            | DeviceError::NotExecutableInMonitorMode
+            | DeviceError::NotExecutableInProgramMode => Self::ServerDeviceBusy,
+            _ => Self::ServerDeviceFailure,
+        }
+    }
+}
+
+impl From<&Error> for Exception {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::Device(error) => (*error).into(),
+            Error::Protocol(ProtocolError::Device(error)) => (*error).into(),
+            // rejected before sending, e.g. beyond the end of the area
+            Error::Protocol(ProtocolError::InvalidAddress(_)) => Self::IllegalDataAddress,
+            Error::Protocol(ProtocolError::InvalidCount(_) | ProtocolError::InvalidValue(_)) => {
+                Self::IllegalDataValue
+            }
+            Error::NotSupported(_) => Self::IllegalFunction,
+            Error::Io(_) | Error::Serial(_) => Self::GatewayTargetFailedToRespond,
+            _ => Self::ServerDeviceFailure,
+        }
+    }
+}
+
+impl From<Error> for Exception {
+    fn from(error: Error) -> Self {
+        (&error).into()
+    }
+}
+
+/// A Modbus TCP server which translates requests into Hostlink commands.
+///
+/// Supported functions are read coils (1), read discrete inputs (2), read holding registers (3),
+/// read input registers (4), write single coil (5), write single register (6),
+/// write multiple coils (15) and write multiple registers (16).
+/// The unit identifier is ignored; every request is sent to the device's node.
+/// Coils are written by reading, modifying and writing back the words that contain them.
+/// # Example
+/// ```rust,no_run
+/// use hostlink::{
+///     device::PlcDevice,
+///     modbus::{Map, ModbusGateway},
+///     protocol::{Address, Area, NodeId},
+/// };
+/// use std::net::TcpListener;
+///
+/// let port = serialport::new("/dev/ttyUSB0", 9600).open().unwrap();
+/// let device = PlcDevice::connect(port, NodeId::new(0).unwrap(), None).unwrap();
+/// let map = Map::new().holding_registers(0, 1000, Address::word(Area::Dm, 0));
+///
+/// ModbusGateway::new(device, map)
+///     .serve(TcpListener::bind("0.0.0.0:502").unwrap())
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ModbusGateway {
+    device: Mutex<PlcDevice>,
+    map: Map,
+}
+
+impl ModbusGateway {
+    /// Creates a gateway forwarding requests to `device`.
+    #[must_use]
+    pub const fn new(device: PlcDevice, map: Map) -> Self {
+        Self {
+            device: Mutex::new(device),
+            map,
+        }
+    }
+
+    /// Returns the address map.
+    #[must_use]
+    pub const fn map(&self) -> &Map {
+        &self.map
+    }
+
+    /// Returns the device requests are forwarded to.
+    pub fn into_device(self) -> PlcDevice {
+        self.device
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Accepts connections and serves each of them on its own thread.
+    /// Requests from different connections are forwarded one at a time.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        std::thread::scope(|scope| {
+            for stream in listener.incoming() {
+                let stream = stream?;
+                scope.spawn(move || self.serve_connection(stream));
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Answers requests (MBAP header followed by a PDU) until the connection is closed.
+    pub fn serve_connection<S: Read + Write>(&self, mut stream: S) -> io::Result<()> {
+        loop {
+            let mut header = [0; 7];
+
+            match stream.read_exact(&mut header) {
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                result => result?,
+            }
+
+            let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+            let mut pdu = vec![0; length.saturating_sub(1)];
+            stream.read_exact(&mut pdu)?;
+
+            // only the Modbus protocol (0) is answered
+            if header[2..4] != [0, 0] {
+                continue;
+            }
+
+            let response = self.handle_pdu(&pdu);
+            #[allow(clippy::cast_possible_truncation)]
+            let length = (response.len() + 1) as u16;
+
+            let mut frame = Vec::with_capacity(header.len() + response.len());
+            frame.extend_from_slice(&header[..4]);
+            frame.extend_from_slice(&length.to_be_bytes());
+            frame.push(header[6]);
+            frame.extend_from_slice(&response);
+
+            stream.write_all(&frame)?;
+            stream.flush()?;
+        }
+    }
+
+    /// Handles a request PDU (function code and data) and returns the response PDU.
+    #[must_use]
+    pub fn handle_pdu(&self, pdu: &[u8]) -> Vec<u8> {
+        let Some(&function) = pdu.first() else {
+            return vec![0x80, Exception::IllegalFunction as u8];
+        };
+
+        match self.execute(function, &pdu[1..]) {
+            Ok(data) => [&[function][..], &data].concat(),
+            Err(exception) => vec![function | 0x80, exception as u8],
+        }
+    }
+
+    fn execute(&self, function: u8, data: &[u8]) -> Result<Vec<u8>, Exception> {
+        let field = |index: usize| -> Result<u16, Exception> {
+            data.get(index..index + 2)
+                .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                .ok_or(Exception::IllegalDataValue)
+        };
+
+        match function {
+            0x01 | 0x02 => {
+                let table = if function == 0x01 {
+                    Table::Coils
+                } else {
+                    Table::DiscreteInputs
+                };
+                let (start, count) = (field(0)?, field(2)?);
+                check_count(count, MAX_READ_BITS)?;
+                let address = self.resolve(table, start, count)?;
+
+                let bits = read_bits(&mut self.device(), address, count)?;
+                let mut bytes = vec![0; bits.len().div_ceil(8)];
+
+                for (index, bit) in bits.iter().enumerate() {
+                    bytes[index / 8] |= u8::from(*bit) << (index % 8);
+                }
+
+                #[allow(clippy::cast_possible_truncation)]
+                Ok([&[bytes.len() as u8][..], &bytes].concat())
+            }
+            0x03 | 0x04 => {
+                let table = if function == 0x03 {
+                    Table::HoldingRegisters
+                } else {
+                    Table::InputRegisters
+                };
+                let (start, count) = (field(0)?, field(2)?);
+                check_count(count, MAX_READ_REGISTERS)?;
+                let address = self.resolve(table, start, count)?;
+
+                let words = self
+                    .device()
+                    .read_words(address.area, address.word, count)?;
+
+                #[allow(clippy::cast_possible_truncation)]
+                let mut response = vec![(words.len() * 2) as u8];
+                response.extend(words.iter().flat_map(|word| word.to_be_bytes()));
+
+                Ok(response)
+            }
+            0x05 => {
+                let (start, value) = (field(0)?, field(2)?);
+                let value = match value {
+                    0xFF00 => true,
+                    0x0000 => false,
+                    _ => return Err(Exception::IllegalDataValue),
+                };
+                let address = self.resolve(Table::Coils, start, 1)?;
+
+                write_bits(&mut self.device(), address, &[value])?;
+
+                Ok(data[..4].to_vec())
+            }
+            0x06 => {
+                let (start, value) = (field(0)?, field(2)?);
+                let address = self.resolve(Table::HoldingRegisters, start, 1)?;
+
+                self.device()
+                    .write_words(address.area, address.word, &[value])?;
+
+                Ok(data[..4].to_vec())
+            }
+            0x0F => {
+                let (start, count) = (field(0)?, field(2)?);
+                check_count(count, MAX_WRITE_BITS)?;
+                let bytes = values(data, usize::from(count).div_ceil(8))?;
+                let address = self.resolve(Table::Coils, start, count)?;
+
+                let bits: Vec<bool> = (0..usize::from(count))
+                    .map(|index| bytes[index / 8] & (1 << (index % 8)) != 0)
+                    .collect();
+                write_bits(&mut self.device(), address, &bits)?;
+
+                Ok(data[..4].to_vec())
+            }
+            0x10 => {
+                let (start, count) = (field(0)?, field(2)?);
+                check_count(count, MAX_WRITE_REGISTERS)?;
+                let bytes = values(data, usize::from(count) * 2)?;
+                let address = self.resolve(Table::HoldingRegisters, start, count)?;
+
+                let words: Vec<u16> = bytes
+                    .chunks(2)
+                    .map(|word| u16::from_be_bytes([word[0], word[1]]))
+                    .collect();
+                self.device()
+                    .write_words(address.area, address.word, &words)?;
+
+                Ok(data[..4].to_vec())
+            }
+            _ => Err(Exception::IllegalFunction),
+        }
+    }
+
+    fn resolve(&self, table: Table, start: u16, count: u16) -> Result<Address, Exception> {
+        self.map
+            .resolve(table, start, count)
+            .ok_or(Exception::IllegalDataAddress)
+    }
+
+    fn device(&self) -> MutexGuard<'_, PlcDevice> {
+        self.device.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn read_bits(device: &mut PlcDevice, address: Address, count: u16) -> Result<Vec<bool>, Error> {
+    let first = usize::from(address.bit.unwrap_or_default());
+    let words = device.read_words(address.area, address.word, word_count(first, count))?;
+
+    Ok((first..first + usize::from(count))
+        .map(|bit| words[bit / 16] & (1 << (bit % 16)) != 0)
+        .collect())
+}
+
+fn write_bits(device: &mut PlcDevice, address: Address, bits: &[bool]) -> Result<(), Error> {
+    let first = usize::from(address.bit.unwrap_or_default());
+    #[allow(clippy::cast_possible_truncation)]
+    let count = word_count(first, bits.len() as u16);
+    let mut words = device.read_words(address.area, address.word, count)?;
+
+    for (bit, value) in (first..).zip(bits) {
+        let mask = 1 << (bit % 16);
+
+        if *value {
+            words[bit / 16] |= mask;
+        } else {
+            words[bit / 16] &= !mask;
+        }
+    }
+
+    device.write_words(address.area, address.word, &words)
+}
+
+/// Returns the number of words spanned by `count` bits, starting at bit `first` of a word.
+#[allow(clippy::cast_possible_truncation)]
+const fn word_count(first: usize, count: u16) -> u16 {
+    (first + count as usize).div_ceil(16) as u16
+}
+
+const fn check_count(count: u16, max: u16) -> Result<(), Exception> {
+    if count == 0 || count > max {
+        return Err(Exception::IllegalDataValue);
+    }
+
+    Ok(())
+}
+
+/// Returns the values of a write multiple request, after checking that its byte count is `expected`.
+fn values(data: &[u8], expected: usize) -> Result<&[u8], Exception> {
+    match data.get(4) {
+        Some(&count) if usize::from(count) == expected => {
+            data.get(5..5 + expected).ok_or(Exception::IllegalDataValue)
+        }
+        _ => Err(Exception::IllegalDataValue),
+    }
+}
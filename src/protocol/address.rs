@@ -0,0 +1,191 @@
+use super::error::error_text;
+use super::{MessageKind, ProtocolError};
+use derive_more::Display;
+use std::str::FromStr;
+
+/// A PLC memory area that can be accessed using word read/write commands.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Area {
+    /// IR and SR area (they share the same address space).
+    #[display(fmt = "IR")]
+    IrSr,
+    /// Link relay area.
+    #[display(fmt = "LR")]
+    Lr,
+    /// Holding relay area.
+    #[display(fmt = "HR")]
+    Hr,
+    /// Auxiliary relay area.
+    #[display(fmt = "AR")]
+    Ar,
+    /// Data memory area.
+    #[display(fmt = "DM")]
+    Dm,
+    /// Timer/counter present values.
+    #[display(fmt = "TC")]
+    Tc,
+}
+
+/// A word or bit address inside a PLC memory area, such as `DM100` or `IR001.03`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Address {
+    /// Memory area
+    pub area: Area,
+    /// Word number
+    pub word: u16,
+    /// Bit number (0..=15) for bit addresses
+    pub bit: Option<u8>,
+}
+
+impl Area {
+    /// All areas, in the order they appear in the manual.
+    pub const ALL: [Self; 6] = [Self::IrSr, Self::Lr, Self::Hr, Self::Ar, Self::Dm, Self::Tc];
+
+    /// Returns the command used to read words from this area.
+    #[must_use]
+    pub const fn read_kind(self) -> MessageKind {
+        match self {
+            Self::IrSr => MessageKind::IrSrAreaRead,
+            Self::Lr => MessageKind::LrAreaRead,
+            Self::Hr => MessageKind::HrAreaRead,
+            Self::Ar => MessageKind::ArAreaRead,
+            Self::Dm => MessageKind::DmAreaRead,
+            Self::Tc => MessageKind::PvRead,
+        }
+    }
+
+    /// Returns the command used to write words into this area.
+    #[must_use]
+    pub const fn write_kind(self) -> MessageKind {
+        match self {
+            Self::IrSr => MessageKind::IrSrAreaWrite,
+            Self::Lr => MessageKind::LrAreaWrite,
+            Self::Hr => MessageKind::HrAreaWrite,
+            Self::Ar => MessageKind::ArAreaWrite,
+            Self::Dm => MessageKind::DmAreaWrite,
+            Self::Tc => MessageKind::PvWrite,
+        }
+    }
+
+    /// Returns the operand name used by forced set/reset commands, if bits of this area can be forced.
+    #[must_use]
+    pub const fn operand(self) -> Option<&'static str> {
+        match self {
+            Self::IrSr => Some("CIO "),
+            Self::Lr => Some("LR  "),
+            Self::Hr => Some("HR  "),
+            Self::Ar => Some("AR  "),
+            Self::Tc => Some("TIM "),
+            Self::Dm => None,
+        }
+    }
+
+    /// Parses an operand name used by forced set/reset commands.
+    #[must_use]
+    pub fn from_operand(operand: &str) -> Option<Self> {
+        match operand {
+            "CIO " => Some(Self::IrSr),
+            "LR  " => Some(Self::Lr),
+            "HR  " => Some(Self::Hr),
+            "AR  " => Some(Self::Ar),
+            "TIM " | "CNT " => Some(Self::Tc),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of words in this area on the largest C200H-series models.
+    #[must_use]
+    pub const fn size(self) -> u16 {
+        match self {
+            Self::IrSr => 256,
+            Self::Lr => 64,
+            Self::Hr => 100,
+            Self::Ar => 28,
+            Self::Dm => 6656,
+            Self::Tc => 512,
+        }
+    }
+}
+
+impl Address {
+    /// Creates a word address.
+    #[must_use]
+    pub const fn word(area: Area, word: u16) -> Self {
+        Self {
+            area,
+            word,
+            bit: None,
+        }
+    }
+
+    /// Creates a bit address.
+    /// If the bit number is higher than 15, an error will be returned.
+    pub fn bit(area: Area, word: u16, bit: u8) -> Result<Self, ProtocolError> {
+        if bit > 15 {
+            return Err(ProtocolError::InvalidAddress(error_text(&format!(
+                "{area}{word:04}.{bit:02}"
+            ))));
+        }
+
+        Ok(Self {
+            area,
+            word,
+            bit: Some(bit),
+        })
+    }
+}
+
+impl FromStr for Area {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "IR" | "SR" => Ok(Self::IrSr),
+            "LR" => Ok(Self::Lr),
+            "HR" => Ok(Self::Hr),
+            "AR" => Ok(Self::Ar),
+            "DM" => Ok(Self::Dm),
+            "TC" => Ok(Self::Tc),
+            _ => Err(ProtocolError::InvalidAddress(error_text(s))),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ProtocolError::InvalidAddress(error_text(s));
+
+        let split = s.find(|ch: char| ch.is_ascii_digit()).ok_or_else(invalid)?;
+        let (area, rest) = s.split_at(split);
+        let area = Area::from_str(area).map_err(|_| invalid())?;
+
+        let (word, bit) = match rest.split_once('.') {
+            Some((word, bit)) => (word, Some(bit)),
+            None => (rest, None),
+        };
+        let word = word.parse().map_err(|_| invalid())?;
+
+        match bit {
+            Some(bit) => bit
+                .parse()
+                .ok()
+                .and_then(|bit| Self::bit(area, word, bit).ok())
+                .ok_or_else(invalid),
+            None => Ok(Self::word(area, word)),
+        }
+    }
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{:04}", self.area, self.word)?;
+
+        if let Some(bit) = self.bit {
+            write!(f, ".{bit:02}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+//! Fixed-capacity storage for frames, params and words, the same with or without `std`.
+
+/// A `heapless::Vec<T, N>` holding at most `N` items.
+pub type Buffer<T, const N: usize> = heapless::Vec<T, N>;
+
+/// Appends as much of `items` as fits, returning the number of items appended.
+pub fn extend<T: Clone, const N: usize>(buffer: &mut Buffer<T, N>, items: &[T]) -> usize {
+    let items = &items[..items.len().min(N - buffer.len())];
+    // can't fail, the items were cut to the remaining capacity
+    let _ = buffer.extend_from_slice(items);
+
+    items.len()
+}
+
+/// Creates a buffer holding `items`, or `None` if they don't fit.
+pub fn from_slice<T: Clone, const N: usize>(items: &[T]) -> Option<Buffer<T, N>> {
+    heapless::Vec::from_slice(items).ok()
+}
@@ -0,0 +1,166 @@
+use super::{fcs::fcs, Message, MessageKind, MessageParams, NodeId, ProtocolError};
+
+/// Length of everything in a frame except the params: `@`, node ID, header code, FCS and terminator.
+const FRAME_OVERHEAD: usize = 1 + 2 + 2 + 2 + 2;
+
+/// The [`Initialize`](MessageKind::Initialize) frame, which has no node ID, params or FCS.
+const INITIALIZE_FRAME: &[u8] = b"@**\r";
+
+/// A Hostlink frame whose params are borrowed, e.g. from a receive buffer.
+///
+/// Decoding and encoding don't allocate; [`Message`] is the owned equivalent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MessageRef<'a> {
+    node: NodeId,
+    kind: MessageKind,
+    params: &'a [u8],
+}
+
+impl<'a> MessageRef<'a> {
+    /// Creates a frame from the specified node ID and ASCII params.
+    #[must_use]
+    pub const fn new(node: NodeId, kind: MessageKind, params: &'a [u8]) -> Self {
+        Self { node, kind, params }
+    }
+
+    #[must_use]
+    pub const fn node(&self) -> NodeId {
+        self.node
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> MessageKind {
+        self.kind
+    }
+
+    /// Returns the params, pointing into the decoded buffer.
+    #[must_use]
+    pub const fn params(&self) -> &'a [u8] {
+        self.params
+    }
+
+    /// Decodes a complete frame, including the terminator, and checks its FCS.
+    /// An [`Initialize`](MessageKind::Initialize) frame is decoded with node ID 0.
+    pub fn decode(frame: &'a [u8]) -> Result<Self, ProtocolError> {
+        if frame.first() != Some(&b'@') {
+            return Err(ProtocolError::MissingAtSymbol);
+        }
+
+        if frame.get(1..3) == Some(MessageKind::Initialize.code().as_bytes()) {
+            return if frame == INITIALIZE_FRAME {
+                Ok(Self::new(NodeId::default(), MessageKind::Initialize, &[]))
+            } else {
+                Err(ProtocolError::MissingTerminator)
+            };
+        }
+
+        let &[tens, ones] = frame.get(1..3).ok_or(ProtocolError::MissingNodeId)? else {
+            unreachable!()
+        };
+        if !tens.is_ascii_digit() || !ones.is_ascii_digit() {
+            return Err(ProtocolError::InvalidNodeId(
+                char::from(tens),
+                char::from(ones),
+            ));
+        }
+        let node = NodeId::new((tens - b'0') * 10 + (ones - b'0'))?;
+
+        let &[first, second] = frame.get(3..5).ok_or(ProtocolError::MissingHeaderCode)? else {
+            unreachable!()
+        };
+        let kind = MessageKind::from_code([first, second]).ok_or(
+            ProtocolError::UnknownHeaderCode(char::from(first), char::from(second)),
+        )?;
+
+        let rest = frame
+            .strip_suffix(b"*\r")
+            .ok_or(ProtocolError::MissingTerminator)?;
+
+        if rest.len() < 7 {
+            return Err(ProtocolError::MissingFcs);
+        }
+
+        // we won't store the FCS, but only check if it matches
+        let (checked, received) = rest.split_at(rest.len() - 2);
+        let expected = fcs(checked)?;
+
+        if expected.digits() != received {
+            return Err(ProtocolError::FcsMismatch {
+                expected,
+                received: (char::from(received[0]), char::from(received[1])),
+            });
+        }
+
+        Ok(Self::new(node, kind, &checked[5..]))
+    }
+
+    /// Returns the length of the encoded frame.
+    #[must_use]
+    pub const fn encoded_len(&self) -> usize {
+        if matches!(self.kind, MessageKind::Initialize) {
+            return INITIALIZE_FRAME.len();
+        }
+
+        FRAME_OVERHEAD + self.params.len()
+    }
+
+    /// Encodes the frame into the start of `buffer` and returns the number of bytes written.
+    /// Fails if `buffer` is shorter than [`encoded_len`](Self::encoded_len).
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, ProtocolError> {
+        let length = self.encoded_len();
+        let frame = buffer
+            .get_mut(..length)
+            .ok_or(ProtocolError::BufferTooSmall(length))?;
+
+        if self.kind == MessageKind::Initialize {
+            frame.copy_from_slice(INITIALIZE_FRAME);
+            return Ok(length);
+        }
+
+        let end = length - 4;
+        let node = *self.node;
+
+        frame[..3].copy_from_slice(&[b'@', b'0' + node / 10, b'0' + node % 10]);
+        frame[3..5].copy_from_slice(self.kind.code().as_bytes());
+        frame[5..end].copy_from_slice(self.params);
+
+        let fcs = fcs(&frame[..end])?.digits();
+        frame[end..].copy_from_slice(&[fcs[0], fcs[1], b'*', b'\r']);
+
+        Ok(length)
+    }
+
+    /// Appends the encoded frame to `buffer`.
+    #[cfg(feature = "std")]
+    pub fn encode_to_vec(&self, buffer: &mut Vec<u8>) -> Result<(), ProtocolError> {
+        let start = buffer.len();
+        buffer.resize(start + self.encoded_len(), 0);
+
+        self.encode(&mut buffer[start..]).map(drop)
+    }
+
+    /// Appends the encoded frame to `buffer`.
+    #[cfg(feature = "bytes")]
+    pub fn encode_to_bytes(&self, buffer: &mut bytes::BytesMut) -> Result<(), ProtocolError> {
+        let start = buffer.len();
+        buffer.resize(start + self.encoded_len(), 0);
+
+        self.encode(&mut buffer[start..]).map(drop)
+    }
+
+    /// Copies the frame into a [`Message`].
+    /// Fails if the params don't fit into [`MessageParams`].
+    pub fn to_message(&self) -> Result<Message, ProtocolError> {
+        Ok(Message::new(
+            self.node,
+            self.kind,
+            MessageParams::from_slice(self.params)?,
+        ))
+    }
+}
+
+impl<'a> From<&'a Message> for MessageRef<'a> {
+    fn from(message: &'a Message) -> Self {
+        Self::new(message.node(), message.kind(), message.params())
+    }
+}
@@ -0,0 +1,349 @@
+//! Each command knows how to decode its response, so sending one with
+//! [`PlcDevice::execute`](crate::device::PlcDevice::execute) returns the decoded data directly.
+
+use super::responses::{
+    model::PcModel,
+    status::{Status, StatusMode},
+    words::{Words, WordsParseError},
+};
+use super::{Address, Area, EasyCommand, ProtocolError, Response, ResponseData, TimerInstruction};
+
+/// A command whose response is decoded into [`Command::Response`].
+pub trait Command {
+    /// The decoded response data.
+    type Response;
+
+    /// Returns the command to send.
+    fn command(&self) -> EasyCommand;
+
+    /// Decodes a response to this command. Fails with the end code if it reports an error.
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError>;
+}
+
+/// Untyped commands, decoded according to the kind of the response.
+impl Command for EasyCommand {
+    type Response = ResponseData;
+
+    fn command(&self) -> EasyCommand {
+        self.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        response.decode()
+    }
+}
+
+/// Fails with the end code if the response reports an error.
+fn check(response: &Response) -> Result<(), ProtocolError> {
+    response.error().map_or(Ok(()), |error| Err(error.into()))
+}
+
+/// Transmits a block of data, which is then repeated by the PLC. Returns the repeated data.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Test(EasyCommand);
+
+impl Test {
+    /// Fails if the data contains characters which can't be sent.
+    pub fn new(data: &str) -> Result<Self, ProtocolError> {
+        EasyCommand::make_test(data).map(Self)
+    }
+}
+
+impl Command for Test {
+    type Response = String;
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        Ok(String::from_utf8_lossy(response.data()).into_owned())
+    }
+}
+
+/// Reads the operating status of the PLC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadStatus;
+
+impl Command for ReadStatus {
+    type Response = Status;
+
+    fn command(&self) -> EasyCommand {
+        EasyCommand::make_status_read()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)?;
+
+        Ok(Status::try_from(response)?)
+    }
+}
+
+/// Changes the operating mode of the PLC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WriteStatus(pub StatusMode);
+
+impl Command for WriteStatus {
+    type Response = ();
+
+    fn command(&self) -> EasyCommand {
+        EasyCommand::make_status_write(self.0)
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)
+    }
+}
+
+/// Reads words from a memory area.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReadArea(EasyCommand);
+
+impl ReadArea {
+    /// See [`EasyCommand::make_area_read`].
+    pub fn new(area: Area, start: u16, count: u16) -> Result<Self, ProtocolError> {
+        EasyCommand::make_area_read(area, start, count).map(Self)
+    }
+}
+
+impl Command for ReadArea {
+    type Response = Vec<u16>;
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)?;
+        let Words(words) = Words::try_from(response)?;
+
+        Ok(words.to_vec())
+    }
+}
+
+/// Writes words into a memory area.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WriteArea(EasyCommand);
+
+impl WriteArea {
+    /// See [`EasyCommand::make_area_write`].
+    pub fn new(area: Area, start: u16, data: &[u16]) -> Result<Self, ProtocolError> {
+        EasyCommand::make_area_write(area, start, data).map(Self)
+    }
+}
+
+impl Command for WriteArea {
+    type Response = ();
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)
+    }
+}
+
+/// Reads the completion flags of timers/counters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReadTcStatus(EasyCommand);
+
+impl ReadTcStatus {
+    /// See [`EasyCommand::make_tc_status_read`].
+    pub fn new(start: u16, count: u16) -> Result<Self, ProtocolError> {
+        EasyCommand::make_tc_status_read(start, count).map(Self)
+    }
+}
+
+impl Command for ReadTcStatus {
+    type Response = Vec<bool>;
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)?;
+
+        response
+            .data()
+            .iter()
+            .map(|byte| match byte {
+                b'0' => Ok(false),
+                b'1' => Ok(true),
+                _ => Err(WordsParseError::InvalidDigit(char::from(*byte)).into()),
+            })
+            .collect()
+    }
+}
+
+/// Writes the completion flags of timers/counters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WriteTcStatus(EasyCommand);
+
+impl WriteTcStatus {
+    /// See [`EasyCommand::make_tc_status_write`].
+    pub fn new(start: u16, flags: &[bool]) -> Result<Self, ProtocolError> {
+        EasyCommand::make_tc_status_write(start, flags).map(Self)
+    }
+}
+
+impl Command for WriteTcStatus {
+    type Response = ();
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)
+    }
+}
+
+/// Reads the set value of a timer/counter instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReadSv(EasyCommand);
+
+impl ReadSv {
+    /// See [`EasyCommand::make_sv_read`].
+    pub fn new(
+        instruction: TimerInstruction,
+        number: u16,
+        program_address: Option<u16>,
+    ) -> Result<Self, ProtocolError> {
+        EasyCommand::make_sv_read(instruction, number, program_address).map(Self)
+    }
+}
+
+impl Command for ReadSv {
+    type Response = u16;
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    /// The set value is sent as 4 decimal digits.
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)?;
+        let data = response.data();
+
+        if data.len() != 4 {
+            return Err(WordsParseError::BadLength(data.len()).into());
+        }
+
+        data.iter().try_fold(0, |value, &byte| {
+            let digit = char::from(byte)
+                .to_digit(10)
+                .ok_or(WordsParseError::InvalidDigit(char::from(byte)))?;
+
+            #[allow(clippy::cast_possible_truncation)]
+            Ok(value * 10 + digit as u16)
+        })
+    }
+}
+
+/// Changes the set value of a timer/counter instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChangeSv(EasyCommand);
+
+impl ChangeSv {
+    /// See [`EasyCommand::make_sv_change`].
+    pub fn new(
+        instruction: TimerInstruction,
+        number: u16,
+        program_address: Option<u16>,
+        value: u16,
+    ) -> Result<Self, ProtocolError> {
+        EasyCommand::make_sv_change(instruction, number, program_address, value).map(Self)
+    }
+}
+
+impl Command for ChangeSv {
+    type Response = ();
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)
+    }
+}
+
+/// Force-sets or force-resets a bit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Force(EasyCommand);
+
+impl Force {
+    /// See [`EasyCommand::make_forced`].
+    pub fn new(address: Address, set: bool) -> Result<Self, ProtocolError> {
+        EasyCommand::make_forced(address, set).map(Self)
+    }
+}
+
+impl Command for Force {
+    type Response = ();
+
+    fn command(&self) -> EasyCommand {
+        self.0.clone()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)
+    }
+}
+
+/// Cancels all forced set/reset bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CancelForced;
+
+impl Command for CancelForced {
+    type Response = ();
+
+    fn command(&self) -> EasyCommand {
+        EasyCommand::make_forced_cancel()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)
+    }
+}
+
+/// Reads the PLC's model code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadModel;
+
+impl Command for ReadModel {
+    type Response = PcModel;
+
+    fn command(&self) -> EasyCommand {
+        EasyCommand::make_model_read()
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)?;
+
+        Ok(PcModel::try_from(response)?)
+    }
+}
+
+/// Reads the PLC's error information. If `clear` is set, errors are cleared after reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReadErrors {
+    pub clear: bool,
+}
+
+impl Command for ReadErrors {
+    type Response = Vec<u16>;
+
+    fn command(&self) -> EasyCommand {
+        EasyCommand::make_error_read(self.clear)
+    }
+
+    fn decode(response: &Response) -> Result<Self::Response, ProtocolError> {
+        check(response)?;
+        let Words(errors) = Words::try_from(response)?;
+
+        Ok(errors.to_vec())
+    }
+}
@@ -0,0 +1,213 @@
+use super::buffer::{self, Buffer};
+use super::{MessageRef, ProtocolError};
+use thiserror::Error;
+
+/// Default maximum length of a frame, including the terminator.
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 2048;
+
+/// Bytes dropped by a [`FrameDecoder`] while looking for frames.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameError {
+    /// Bytes outside of a frame (before the next `@`) were dropped.
+    #[error("{0} bytes of noise dropped")]
+    Noise(usize),
+    /// A frame was cut off by the start of the next one and dropped.
+    #[error("unterminated frame of {0} bytes dropped")]
+    Unterminated(usize),
+    /// A frame exceeded the maximum length and was dropped.
+    #[error("frame longer than {0} bytes dropped")]
+    TooLong(usize),
+}
+
+/// An incremental decoder, splitting a stream of bytes received in arbitrary chunks into frames.
+///
+/// A frame starts with `@` and ends with `\r`. Noise before a frame and frames which are cut off
+/// by the next `@` or grow beyond the maximum length are dropped and reported as errors;
+/// decoding resumes at the next `@`.
+///
+/// At most [`DEFAULT_MAX_FRAME_LENGTH`] bytes are buffered, so the maximum length can only be
+/// lowered.
+/// # Example
+/// ```rust
+/// use hostlink::protocol::{FrameDecoder, FrameError};
+///
+/// let mut decoder = FrameDecoder::new();
+/// decoder.push(b"\0\0@00TS12");
+///
+/// assert_eq!(decoder.next_frame(), Some(Err(FrameError::Noise(2))));
+/// assert_eq!(decoder.next_frame(), None);
+///
+/// decoder.push(b"3447*\r@00");
+///
+/// assert_eq!(decoder.next_frame(), Some(Ok(&b"@00TS123447*\r"[..])));
+/// assert_eq!(decoder.next_frame(), None);
+/// assert_eq!(decoder.pending(), b"@00");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameDecoder {
+    buffer: Buffer<u8, DEFAULT_MAX_FRAME_LENGTH>,
+    /// Length of the frame last returned, which is still at the start of the buffer
+    consumed: usize,
+    /// Whether the rest of a frame that was too long is being dropped
+    discarding: bool,
+    max_length: usize,
+}
+
+impl FrameDecoder {
+    /// Creates a decoder with the default maximum frame length.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            buffer: Buffer::new(),
+            consumed: 0,
+            discarding: false,
+            max_length: DEFAULT_MAX_FRAME_LENGTH,
+        }
+    }
+
+    /// Sets the maximum length of a frame, including the terminator.
+    #[must_use]
+    pub const fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Appends received bytes and returns how many were accepted.
+    /// Only falls short once the buffer is full; call [`next_frame`](Self::next_frame) to make room.
+    pub fn push(&mut self, bytes: &[u8]) -> usize {
+        buffer::extend(&mut self.buffer, bytes)
+    }
+
+    /// Returns the next complete frame (including the terminator), or the reason bytes were dropped.
+    /// Returns `None` if more bytes are needed.
+    pub fn next_frame(&mut self) -> Option<Result<&[u8], FrameError>> {
+        self.remove_front(self.consumed);
+        self.consumed = 0;
+
+        if self.discarding {
+            // drop the rest of the frame that was too long, up to its terminator or the next frame
+            let Some(index) = self
+                .buffer
+                .iter()
+                .position(|&byte| byte == b'@' || byte == b'\r')
+            else {
+                self.buffer.clear();
+                return None;
+            };
+
+            let end = if self.buffer[index] == b'\r' {
+                index + 1
+            } else {
+                index
+            };
+            self.remove_front(end);
+            self.discarding = false;
+        }
+
+        if *self.buffer.first()? != b'@' {
+            let noise = self
+                .buffer
+                .iter()
+                .position(|&byte| byte == b'@')
+                .unwrap_or(self.buffer.len());
+            self.remove_front(noise);
+
+            return Some(Err(FrameError::Noise(noise)));
+        }
+
+        let end = self.buffer[1..]
+            .iter()
+            .position(|&byte| byte == b'@' || byte == b'\r')
+            .map(|index| index + 1);
+
+        match end {
+            Some(index) if self.buffer[index] == b'@' => {
+                self.remove_front(index);
+                Some(Err(FrameError::Unterminated(index)))
+            }
+            Some(index) if index < self.max_length => {
+                self.consumed = index + 1;
+                Some(Ok(&self.buffer[..=index]))
+            }
+            Some(index) => {
+                self.remove_front(index + 1);
+                Some(Err(FrameError::TooLong(self.max_length)))
+            }
+            None if self.buffer.len() >= self.max_length => {
+                self.buffer.clear();
+                self.discarding = true;
+                Some(Err(FrameError::TooLong(self.max_length)))
+            }
+            None => None,
+        }
+    }
+
+    /// Returns the next complete frame, decoded.
+    pub fn next_message(&mut self) -> Option<Result<MessageRef<'_>, ProtocolError>> {
+        self.next_frame()
+            .map(|frame| MessageRef::decode(frame.map_err(ProtocolError::from)?))
+    }
+
+    /// Returns the bytes received since the last complete frame, e.g. the start of a frame
+    /// which is still being received.
+    #[must_use]
+    pub fn pending(&self) -> &[u8] {
+        &self.buffer[self.consumed..]
+    }
+
+    /// Drops the first `count` bytes. Unlike `drain`, this works for both kinds of `Buffer`.
+    fn remove_front(&mut self, count: usize) {
+        self.buffer.copy_within(count.., 0);
+        self.buffer.truncate(self.buffer.len() - count);
+    }
+
+    /// Drops everything received so far.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.consumed = 0;
+        self.discarding = false;
+    }
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Yields decoded messages, skipping noise and unterminated frames.
+#[cfg(feature = "tokio-util")]
+impl tokio_util::codec::Decoder for FrameDecoder {
+    type Item = super::Message;
+    type Error = crate::device::Error;
+
+    fn decode(&mut self, src: &mut bytes::BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        self.push(src);
+        src.clear();
+
+        loop {
+            match self.next_frame() {
+                Some(Ok(frame)) => return Ok(Some(MessageRef::decode(frame)?.to_message()?)),
+                Some(Err(FrameError::Noise(..) | FrameError::Unterminated(..))) => (),
+                Some(Err(error)) => return Err(ProtocolError::from(error).into()),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Encodes messages, so that a `FrameDecoder` can be used with `Framed`.
+#[cfg(feature = "tokio-util")]
+impl tokio_util::codec::Encoder<super::Message> for FrameDecoder {
+    type Error = crate::device::Error;
+
+    fn encode(
+        &mut self,
+        item: super::Message,
+        dst: &mut bytes::BytesMut,
+    ) -> Result<(), Self::Error> {
+        MessageRef::from(&item).encode_to_bytes(dst)?;
+
+        Ok(())
+    }
+}
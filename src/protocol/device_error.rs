@@ -0,0 +1,162 @@
+use super::{MessageKind, ProtocolError};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeviceError {
+    #[error("No error")]
+    None,
+    #[error("Not executable in RUN mode")]
+    NotExecutableInRunMode,
+    #[error("Not executable in MONITOR mode")]
+    NotExecutableInMonitorMode,
+    #[error("Not executable with PROM mounted")]
+    NotExecutableWithPromMounted,
+    #[error("Address over (data overflow)")]
+    AddressOver,
+    #[error("1/0 REGISTER capacity exceeded (no registration made), I/0 READ unexecutable.")]
+    IoRegisterCapacityExceeded,
+    #[error("Not executable in PROGRAM mode")]
+    NotExecutableInProgramMode,
+    #[error("Parity error")]
+    ParityError,
+    #[error("Framing error")]
+    FramingError,
+    #[error("Overrun")]
+    Overrun,
+    #[error("FCS error")]
+    FCSError,
+    #[error("Format error (parameter length error)")]
+    FormatError,
+    #[error("Entry number data error (parameter error, data code error, data length error)")]
+    EntryNumberData,
+    #[error("Instruction not found")]
+    InstructionNotFound,
+    #[error("Frame length error")]
+    FrameLengthError,
+    #[error(
+        "Not executable (due to unexecutable error clear, non-registration of I/O table, etc.)"
+    )]
+    NotExecutable,
+    #[error("Aborted due to parity error in transmit data")]
+    BadParity,
+    #[error("Aborted due to framing error in transmit data")]
+    BadFraming,
+    #[error("Aborted due to overrun in transmit data")]
+    TransmitDataOverrun,
+    #[error("Aborted due to format error in transmit data")]
+    Format,
+    #[error("Aborted due to entry number data error in transmit data")]
+    IllegalEntryNumber,
+    #[error("Aborted due to frame length error in transmit data")]
+    IllegalFrameLength,
+    /// The PLC answered with [`UndefinedCommand`](MessageKind::UndefinedCommand) instead of an end
+    /// code, because it doesn't support this command.
+    #[error("Command not supported by the PLC: {0}")]
+    UnsupportedCommand(MessageKind),
+}
+
+impl DeviceError {
+    pub const fn to_result(self) -> Result<(), Self> {
+        if matches!(self, Self::None) {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    #[must_use]
+    pub const fn is_ok(self) -> bool {
+        self.to_result().is_ok()
+    }
+
+    /// Returns whether the PLC failed to receive the command correctly (parity, framing, FCS errors, etc.).
+    #[must_use]
+    pub const fn is_transmission_error(self) -> bool {
+        matches!(
+            self,
+            Self::ParityError
+                | Self::FramingError
+                | Self::Overrun
+                | Self::FCSError
+                | Self::FrameLengthError
+                | Self::BadParity
+                | Self::BadFraming
+                | Self::TransmitDataOverrun
+                | Self::IllegalFrameLength
+        )
+    }
+
+    /// Returns the end code sent by the PLC for this error.
+    /// [`UnsupportedCommand`](Self::UnsupportedCommand) is sent as the `IC` header code instead.
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::None => "00",
+            Self::NotExecutableInRunMode => "01",
+            Self::NotExecutableInMonitorMode => "02",
+            Self::NotExecutableWithPromMounted => "03",
+            Self::AddressOver => "04",
+            Self::IoRegisterCapacityExceeded => "09",
+            Self::NotExecutableInProgramMode => "0B",
+            Self::ParityError => "10",
+            Self::FramingError => "11",
+            Self::Overrun => "12",
+            Self::FCSError => "13",
+            Self::FormatError => "14",
+            Self::EntryNumberData => "15",
+            Self::InstructionNotFound => "16",
+            Self::FrameLengthError => "18",
+            Self::NotExecutable => "19",
+            Self::BadParity => "A0",
+            Self::BadFraming => "A1",
+            Self::TransmitDataOverrun => "A2",
+            Self::Format => "A4",
+            Self::IllegalEntryNumber => "A5",
+            Self::IllegalFrameLength => "A8",
+            Self::UnsupportedCommand(..) => "IC",
+        }
+    }
+}
+
+impl TryFrom<(char, char)> for DeviceError {
+    type Error = ProtocolError;
+
+    fn try_from(value: (char, char)) -> Result<Self, Self::Error> {
+        match value {
+            ('0', '0') => Ok(Self::None),
+            ('0', '1') => Ok(Self::NotExecutableInRunMode),
+            ('0', '2') => Ok(Self::NotExecutableInMonitorMode),
+            ('0', '3') => Ok(Self::NotExecutableWithPromMounted),
+            ('0', '4') => Ok(Self::AddressOver),
+            ('0', '9') => Ok(Self::IoRegisterCapacityExceeded),
+            ('0', 'B') => Ok(Self::NotExecutableInProgramMode),
+            ('1', '0') => Ok(Self::ParityError),
+            ('1', '1') => Ok(Self::FramingError),
+            ('1', '2') => Ok(Self::Overrun),
+            ('1', '3') => Ok(Self::FCSError),
+            ('1', '4') => Ok(Self::FormatError),
+            ('1', '5') => Ok(Self::EntryNumberData),
+            ('1', '6') => Ok(Self::InstructionNotFound),
+            ('1', '8') => Ok(Self::FrameLengthError),
+            ('1', '9') => Ok(Self::NotExecutable),
+            ('A', '0') => Ok(Self::BadParity),
+            ('A', '1') => Ok(Self::BadFraming),
+            ('A', '2') => Ok(Self::TransmitDataOverrun),
+            ('A', '4') => Ok(Self::Format),
+            ('A', '5') => Ok(Self::IllegalEntryNumber),
+            ('A', '8') => Ok(Self::IllegalFrameLength),
+            _ => Err(Self::Error::UnknownErrorCode(value.0, value.1)),
+        }
+    }
+}
+
+impl TryFrom<&str> for DeviceError {
+    type Error = ProtocolError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.as_bytes() {
+            &[first, second] => Self::try_from((char::from(first), char::from(second))),
+            _ => Err(Self::Error::ErrorCodeBadLength),
+        }
+    }
+}
@@ -1,7 +1,21 @@
-use super::{Message, MessageKind, MessageParams, NodeId, ProtocolError};
+use super::error::error_text;
+use super::responses::{status::StatusMode, words::Words};
+use super::{Address, Area, Message, MessageKind, MessageParams, NodeId, ProtocolError};
 use derive_more::Display;
 
+/// Maximum number of words read by a single command, so that the response fits into a single frame.
+pub const MAX_READ_WORDS: u16 = 30;
+
+/// Maximum number of words written by a single command, so that it fits into a single frame.
+pub const MAX_WRITE_WORDS: usize = 29;
+
+/// Maximum length of the block of data sent by a test command, so that it fits into a single frame.
+pub const MAX_TEST_LENGTH: usize = 122;
+
 /// A simplified representation of a command.
+///
+/// Commands are validated when they're constructed using the `make_*` functions,
+/// so converting them [`into_message`](Self::into_message) can't fail.
 #[derive(Debug, Display, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[non_exhaustive]
 pub enum EasyCommand {
@@ -9,10 +23,94 @@ pub enum EasyCommand {
     Test(Box<str>),
     /// Reads the operating status of the PLC.
     StatusRead,
+    /// Changes the operating mode of the PLC.
+    #[display(fmt = "STATUS WRITE {_0}")]
+    StatusWrite(StatusMode),
+    /// Reads words from a memory area (timer/counter PVs for [`Area::Tc`]).
+    #[display(fmt = "READ {} {count}", "Address::word(*area, *start)")]
+    AreaRead { area: Area, start: u16, count: u16 },
+    /// Writes words into a memory area (timer/counter PVs for [`Area::Tc`]).
+    #[display(
+        fmt = "WRITE {} {}",
+        "Address::word(*area, *start)",
+        "Words::encode(data)"
+    )]
+    AreaWrite {
+        area: Area,
+        start: u16,
+        data: Vec<u16>,
+    },
+    /// Reads the completion flags of timers/counters.
+    #[display(fmt = "TC STATUS READ {start:04} {count}")]
+    TcStatusRead { start: u16, count: u16 },
+    /// Writes the completion flags of timers/counters.
+    #[display(fmt = "TC STATUS WRITE {start:04} ({} flags)", "flags.len()")]
+    TcStatusWrite { start: u16, flags: Vec<bool> },
+    /// Reads the set value of a timer/counter instruction, optionally at a program address.
+    #[display(fmt = "SV READ {instruction}{number:04}")]
+    SvRead {
+        instruction: TimerInstruction,
+        number: u16,
+        program_address: Option<u16>,
+    },
+    /// Changes the set value of a timer/counter instruction, optionally at a program address.
+    #[display(fmt = "SV CHANGE {instruction}{number:04} {value:04}")]
+    SvChange {
+        instruction: TimerInstruction,
+        number: u16,
+        program_address: Option<u16>,
+        value: u16,
+    },
+    /// Force-sets (`set == true`) or force-resets a bit.
+    #[display(
+        fmt = "{} {address}",
+        "if *set { \"FORCED SET\" } else { \"FORCED RESET\" }"
+    )]
+    Forced { address: Address, set: bool },
+    /// Cancels all forced set/reset bits.
+    ForcedCancel,
+    /// Reads the PLC's model code.
+    PcModelRead,
+    /// Reads the PLC's error information, clearing the errors if `clear` is set.
+    #[display(fmt = "ERROR READ{}", "if *clear { \" (clear)\" } else { \"\" }")]
+    ErrorRead { clear: bool },
+    /// Cancels a multi-frame exchange. The PLC doesn't respond.
+    Abort,
+    /// Resets the transmission control of every unit on the line. No unit responds.
+    Initialize,
+}
+
+/// A timer/counter instruction whose set value can be read or changed.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TimerInstruction {
+    #[display(fmt = "TIM")]
+    Tim,
+    #[display(fmt = "TIMH")]
+    Timh,
+    #[display(fmt = "CNT")]
+    Cnt,
+    #[display(fmt = "CNTR")]
+    Cntr,
+}
+
+impl TimerInstruction {
+    /// Returns the instruction name as sent in SV commands, padded to 4 characters.
+    #[must_use]
+    pub const fn operand(self) -> &'static str {
+        match self {
+            Self::Tim => "TIM ",
+            Self::Timh => "TIMH",
+            Self::Cnt => "CNT ",
+            Self::Cntr => "CNTR",
+        }
+    }
 }
 
 impl EasyCommand {
     /// Construct a `Test` command with the given data.
+    ///
+    /// The data may contain up to [`MAX_TEST_LENGTH`] letters, digits, spaces and punctuation except
+    /// `@`, which would be taken as the start of the next frame.
     /// # Example
     /// ```rust
     /// use hostlink::protocol::{EasyCommand, Message, MessageKind, MessageParams, NodeId};
@@ -27,7 +125,7 @@ impl EasyCommand {
     /// let easy_test = EasyCommand::make_test(test_message).unwrap();
     ///
     /// // Same, using the more complex API:
-    /// let params = MessageParams::from(test_message);
+    /// let params = MessageParams::try_from(test_message).unwrap();
     /// let complex_test = Message::new(node, MessageKind::Test, params);
     ///
     /// // They're the same
@@ -36,9 +134,10 @@ impl EasyCommand {
     pub fn make_test<S: AsRef<str>>(data: S) -> Result<Self, ProtocolError> {
         let data = data.as_ref();
 
-        if !data
-            .chars()
-            .all(|ch| ch.is_ascii_alphanumeric() || ch.is_ascii_punctuation() || ch == ' ')
+        if data.len() > MAX_TEST_LENGTH
+            || !data
+                .chars()
+                .all(|ch| (ch.is_ascii_graphic() && ch != '@') || ch == ' ')
         {
             return Err(ProtocolError::InvalidTestData);
         }
@@ -68,15 +167,157 @@ impl EasyCommand {
         Self::StatusRead
     }
 
-    /// Perform conversion into [`Message`](Message).
+    /// Construct a `StatusWrite` command, changing the operating mode.
     #[must_use]
-    pub fn into_message(self, node: NodeId) -> Message {
-        let kind = self.kind();
+    pub const fn make_status_write(mode: StatusMode) -> Self {
+        Self::StatusWrite(mode)
+    }
 
-        match self {
-            Self::Test(data) => Message::new(node, kind, data.into()),
-            Self::StatusRead => Message::new_with_empty_params(node, kind),
+    /// Construct an `AreaRead` command, reading `count` words starting at word `start`.
+    /// Word numbers have 4 digits and at most [`MAX_READ_WORDS`] can be read at once.
+    /// # Example
+    /// ```rust
+    /// use hostlink::protocol::{Area, EasyCommand, Message, MessageKind, NodeId};
+    ///
+    /// let node = NodeId::new(0).unwrap();
+    /// let easy_read = EasyCommand::make_area_read(Area::Dm, 100, 2).unwrap();
+    /// let complex_read = Message::new(node, MessageKind::DmAreaRead, "01000002".try_into().unwrap());
+    ///
+    /// assert_eq!(&easy_read, &complex_read);
+    /// assert!(EasyCommand::make_area_read(Area::Dm, 9999, 2).is_err());
+    /// ```
+    pub fn make_area_read(area: Area, start: u16, count: u16) -> Result<Self, ProtocolError> {
+        if count == 0 || count > MAX_READ_WORDS {
+            return Err(ProtocolError::InvalidCount(count.into()));
+        }
+        check_range(area, start, count.into())?;
+
+        Ok(Self::AreaRead { area, start, count })
+    }
+
+    /// Construct an `AreaWrite` command, writing `data` starting at word `start`.
+    /// Word numbers have 4 digits and at most [`MAX_WRITE_WORDS`] can be written at once.
+    pub fn make_area_write(area: Area, start: u16, data: &[u16]) -> Result<Self, ProtocolError> {
+        if data.is_empty() || data.len() > MAX_WRITE_WORDS {
+            return Err(ProtocolError::InvalidCount(data.len()));
+        }
+        check_range(area, start, data.len())?;
+
+        Ok(Self::AreaWrite {
+            area,
+            start,
+            data: data.to_vec(),
+        })
+    }
+
+    /// Construct a `TcStatusRead` command, reading `count` completion flags starting at `start`.
+    pub fn make_tc_status_read(start: u16, count: u16) -> Result<Self, ProtocolError> {
+        if count == 0 {
+            return Err(ProtocolError::InvalidCount(0));
+        }
+        check_range(Area::Tc, start, count.into())?;
+
+        Ok(Self::TcStatusRead { start, count })
+    }
+
+    /// Construct a `TcStatusWrite` command, writing completion flags starting at `start`.
+    pub fn make_tc_status_write(start: u16, flags: &[bool]) -> Result<Self, ProtocolError> {
+        if flags.is_empty() {
+            return Err(ProtocolError::InvalidCount(0));
+        }
+        check_range(Area::Tc, start, flags.len())?;
+
+        Ok(Self::TcStatusWrite {
+            start,
+            flags: flags.to_vec(),
+        })
+    }
+
+    /// Construct an `SvRead` command for timer/counter `number`.
+    /// With a `program_address`, the instruction at that address is read (`SV READ 2`).
+    pub fn make_sv_read(
+        instruction: TimerInstruction,
+        number: u16,
+        program_address: Option<u16>,
+    ) -> Result<Self, ProtocolError> {
+        check_sv_target(number, program_address)?;
+
+        Ok(Self::SvRead {
+            instruction,
+            number,
+            program_address,
+        })
+    }
+
+    /// Construct an `SvChange` command, setting the set value of timer/counter `number` to `value`.
+    /// With a `program_address`, the instruction at that address is changed (`SV CHANGE 2`).
+    pub fn make_sv_change(
+        instruction: TimerInstruction,
+        number: u16,
+        program_address: Option<u16>,
+        value: u16,
+    ) -> Result<Self, ProtocolError> {
+        check_sv_target(number, program_address)?;
+
+        if value > 9999 {
+            return Err(ProtocolError::InvalidValue(value));
         }
+
+        Ok(Self::SvChange {
+            instruction,
+            number,
+            program_address,
+            value,
+        })
+    }
+
+    /// Construct a `Forced` command, force-setting (`set == true`) or force-resetting a bit.
+    /// The address must be a bit address in an area whose bits can be forced.
+    pub fn make_forced(address: Address, set: bool) -> Result<Self, ProtocolError> {
+        if address.area.operand().is_none() || address.bit.is_none() {
+            return Err(ProtocolError::InvalidAddress(error_text(
+                &address.to_string(),
+            )));
+        }
+        check_range(address.area, address.word, 1)?;
+
+        Ok(Self::Forced { address, set })
+    }
+
+    /// Construct a `ForcedCancel` command.
+    #[must_use]
+    pub const fn make_forced_cancel() -> Self {
+        Self::ForcedCancel
+    }
+
+    /// Construct a `PcModelRead` command.
+    #[must_use]
+    pub const fn make_model_read() -> Self {
+        Self::PcModelRead
+    }
+
+    /// Construct an `ErrorRead` command. If `clear` is set, errors are cleared after reading.
+    #[must_use]
+    pub const fn make_error_read(clear: bool) -> Self {
+        Self::ErrorRead { clear }
+    }
+
+    /// Construct an `Abort` command.
+    #[must_use]
+    pub const fn make_abort() -> Self {
+        Self::Abort
+    }
+
+    /// Construct an `Initialize` command. Its frame has no node ID, so the node is ignored.
+    #[must_use]
+    pub const fn make_initialize() -> Self {
+        Self::Initialize
+    }
+
+    /// Perform conversion into [`Message`](Message).
+    #[must_use]
+    pub fn into_message(self, node: NodeId) -> Message {
+        Message::new(node, self.kind(), self.params())
     }
 
     /// Get the command's message type.
@@ -85,19 +326,120 @@ impl EasyCommand {
         match self {
             Self::Test(..) => MessageKind::Test,
             Self::StatusRead => MessageKind::StatusRead,
+            Self::StatusWrite(..) => MessageKind::StatusWrite,
+            Self::AreaRead { area, .. } => area.read_kind(),
+            Self::AreaWrite { area, .. } => area.write_kind(),
+            Self::TcStatusRead { .. } => MessageKind::TcStatusRead,
+            Self::TcStatusWrite { .. } => MessageKind::TcStatusWrite,
+            Self::SvRead {
+                program_address: None,
+                ..
+            } => MessageKind::SvRead1,
+            Self::SvRead { .. } => MessageKind::SvRead2,
+            Self::SvChange {
+                program_address: None,
+                ..
+            } => MessageKind::SvChange1,
+            Self::SvChange { .. } => MessageKind::SvChange2,
+            Self::Forced { set: true, .. } => MessageKind::ForcedSet,
+            Self::Forced { set: false, .. } => MessageKind::ForcedReset,
+            Self::ForcedCancel => MessageKind::ForcedSetResetCancel,
+            Self::PcModelRead => MessageKind::PcModelRead,
+            Self::ErrorRead { .. } => MessageKind::ErrorRead,
+            Self::Abort => MessageKind::Abort,
+            Self::Initialize => MessageKind::Initialize,
         }
     }
 
     fn params(&self) -> MessageParams {
-        match self {
-            Self::Test(string) => string.clone().into(),
-            Self::StatusRead => MessageParams::new(),
-        }
+        let params = match self {
+            Self::Test(string) => return text_params(string),
+            Self::StatusRead
+            | Self::ForcedCancel
+            | Self::PcModelRead
+            | Self::Abort
+            | Self::Initialize => return MessageParams::new(),
+            Self::StatusWrite(mode) => return text_params(mode.write_code()),
+            Self::ErrorRead { clear: false } => return text_params("00"),
+            Self::ErrorRead { clear: true } => return text_params("01"),
+            Self::AreaRead { start, count, .. } | Self::TcStatusRead { start, count } => {
+                format!("{start:04}{count:04}")
+            }
+            Self::AreaWrite { start, data, .. } => format!("{start:04}{}", Words::encode(data)),
+            Self::TcStatusWrite { start, flags } => {
+                let flags: String = flags
+                    .iter()
+                    .map(|flag| if *flag { '1' } else { '0' })
+                    .collect();
+
+                format!("{start:04}{flags}")
+            }
+            Self::SvRead {
+                instruction,
+                number,
+                program_address,
+            } => sv_target(*instruction, *number, *program_address),
+            Self::SvChange {
+                instruction,
+                number,
+                program_address,
+                value,
+            } => format!(
+                "{}{value:04}",
+                sv_target(*instruction, *number, *program_address)
+            ),
+            Self::Forced { address, .. } => format!(
+                "{}{:04}{:02}",
+                address.area.operand().unwrap_or_default(),
+                address.word,
+                address.bit.unwrap_or_default()
+            ),
+        };
+
+        text_params(&params)
     }
 }
 
+/// Converts params of a command, whose length was checked when it was constructed.
+fn text_params(params: &str) -> MessageParams {
+    // can't fail, every command fits into a single frame
+    MessageParams::from_slice(params.as_bytes()).unwrap_or_default()
+}
+
 impl PartialEq<Message> for EasyCommand {
     fn eq(&self, other: &Message) -> bool {
         self.kind() == other.kind() && &self.params() == other.params()
     }
 }
+
+/// Checks that `count` words starting at `start` can be addressed using 4 digit word numbers.
+/// The actual size of the area depends on the model, so it's left to the PLC to check
+/// (it responds with [`AddressOver`](super::DeviceError::AddressOver)).
+fn check_range(area: Area, start: u16, count: usize) -> Result<(), ProtocolError> {
+    if usize::from(start) + count > 10_000 {
+        return Err(ProtocolError::InvalidAddress(error_text(
+            &Address::word(area, start).to_string(),
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks the timer/counter number and program address of an SV command.
+fn check_sv_target(number: u16, program_address: Option<u16>) -> Result<(), ProtocolError> {
+    check_range(Area::Tc, number, 1)?;
+
+    match program_address {
+        Some(address) if address > 9999 => Err(ProtocolError::InvalidValue(address)),
+        _ => Ok(()),
+    }
+}
+
+/// Formats the program address, instruction and timer/counter number of an SV command.
+fn sv_target(instruction: TimerInstruction, number: u16, program_address: Option<u16>) -> String {
+    let program_address = program_address
+        .map(|address| format!("{address:04}"))
+        .unwrap_or_default();
+
+    format!("{program_address}{}{number:04}", instruction.operand())
+}
@@ -1,10 +1,33 @@
-use super::responses::status::StatusParseError;
-use crate::device::DeviceError;
-use std::num::ParseIntError;
+use super::fcs::FcsBytes;
+use super::responses::{model::ModelParseError, status::StatusParseError, words::WordsParseError};
+use super::{DeviceError, FrameError};
+#[cfg(feature = "std")]
+use crate::fins::FinsParseError;
 use thiserror::Error;
 
+/// Maximum number of bytes of the input quoted by an [`Error`].
+pub const MAX_ERROR_TEXT: usize = 32;
+
+/// Input quoted by an [`Error`], cut off after [`MAX_ERROR_TEXT`] bytes.
+pub type ErrorText = heapless::String<MAX_ERROR_TEXT>;
+
+/// Copies as much of `text` as fits into an [`ErrorText`], cutting it at a character boundary.
+pub(crate) fn error_text(text: &str) -> ErrorText {
+    let mut end = text.len().min(MAX_ERROR_TEXT);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    // can't fail, the text was cut to the capacity
+    ErrorText::try_from(&text[..end]).unwrap_or_default()
+}
+
 /// Represents a protocol error.
+///
+/// Non-exhaustive: [`FinsParse`](Self::FinsParse) only exists with the `std` feature, and enabling
+/// it mustn't break matches in crates built without it.
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Error {
     /// Invalid Node ID.
     #[error("Node ID must be 0..=99, got '{0}'")]
@@ -14,13 +37,13 @@ pub enum Error {
     #[error("Expected '@' to be the first character")]
     MissingAtSymbol,
 
-    /// Invalid or missing Node ID.
+    /// Missing Node ID.
     #[error("Expected 2 characters for Node ID")]
     MissingNodeId,
 
-    /// Failed to parse string as integer.
-    #[error("Failed to parse string as integer: {0}")]
-    IntParse(#[from] ParseIntError),
+    /// Node ID isn't a decimal number.
+    #[error("Invalid Node ID: '{0}{1}'")]
+    InvalidNodeId(char, char),
 
     /// Missing header code (command code).
     #[error("Missing header (command) code")]
@@ -28,7 +51,11 @@ pub enum Error {
 
     /// Unknown or unsupported command type.
     #[error("Unknown or unsupported command: {0}")]
-    UnknownCommand(String),
+    UnknownCommand(ErrorText),
+
+    /// Unknown or unsupported header code in a frame.
+    #[error("Unknown or unsupported header code: '{0}{1}'")]
+    UnknownHeaderCode(char, char),
 
     /// Missing command terminator.
     #[error("Expected terminator at end of command")]
@@ -38,6 +65,13 @@ pub enum Error {
     #[error("Missing FCS checksum")]
     MissingFcs,
 
+    /// The FCS checksum doesn't match the frame's contents.
+    #[error("FCS mismatch: expected '{expected}', got '{}{}'", received.0, received.1)]
+    FcsMismatch {
+        expected: FcsBytes,
+        received: (char, char),
+    },
+
     /// Test command's message block contains invalid characters.
     #[error("Message block has illegal characters")]
     InvalidTestData,
@@ -51,6 +85,60 @@ pub enum Error {
     #[error("Device error: {0}")]
     Device(#[from] DeviceError),
 
-    #[error("Device error: {0}")]
+    #[error("Failed to parse status: {0}")]
     StatusParse(#[from] StatusParseError),
+
+    #[error("Failed to parse words: {0}")]
+    WordsParse(#[from] WordsParseError),
+
+    #[error("Failed to parse model: {0}")]
+    ModelParse(#[from] ModelParseError),
+
+    #[cfg(feature = "std")]
+    #[error("FINS response: {0}")]
+    FinsParse(#[from] FinsParseError),
+
+    /// Bytes were dropped while looking for a frame.
+    #[error("Framing error: {0}")]
+    Frame(#[from] FrameError),
+
+    /// The buffer can't hold the encoded frame, or params exceed their capacity.
+    #[error("Buffer too small, {0} bytes needed")]
+    BufferTooSmall(usize),
+
+    /// The frame can't be represented as a string.
+    #[error("Frame is not valid UTF-8")]
+    InvalidUtf8,
+
+    /// Number of words or flags out of range for the command.
+    #[error("Invalid number of words or flags: {0}")]
+    InvalidCount(usize),
+
+    /// Set value or program address out of range (0..=9999).
+    #[error("Value out of range: {0}")]
+    InvalidValue(u16),
+
+    /// Invalid memory area address.
+    #[error("Invalid address: '{0}'")]
+    InvalidAddress(ErrorText),
+}
+
+impl Error {
+    /// Returns whether a received frame was malformed or corrupted on the line,
+    /// as opposed to being valid but not what was expected.
+    #[must_use]
+    pub const fn is_framing_error(&self) -> bool {
+        matches!(
+            self,
+            Self::MissingAtSymbol
+                | Self::MissingNodeId
+                | Self::InvalidNodeId(..)
+                | Self::MissingHeaderCode
+                | Self::MissingTerminator
+                | Self::MissingFcs
+                | Self::FcsMismatch { .. }
+                | Self::Frame(..)
+                | Self::InvalidUtf8
+        )
+    }
 }
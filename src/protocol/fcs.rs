@@ -1,17 +1,17 @@
 use super::ProtocolError;
-use std::fmt::Display;
+use core::fmt::Display;
 
 /// FCS Checksum bytes.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct FcsBytes(u8, u8);
 
 /// Calculates the FCS checksum from a serialized Hostlink command.
-/// The input string must only contain characters which are actually needed for the checksum.
-/// If the input string contains unneeded characters, they will also be accounted into the checksum.
-pub fn fcs(cmd_fcs_range: &str) -> Result<FcsBytes, ProtocolError> {
+/// The input must only contain characters which are actually needed for the checksum.
+/// If the input contains unneeded characters, they will also be accounted into the checksum.
+pub fn fcs(cmd_fcs_range: impl AsRef<[u8]>) -> Result<FcsBytes, ProtocolError> {
     let mut fcs = 0;
 
-    for byte in cmd_fcs_range.bytes() {
+    for &byte in cmd_fcs_range.as_ref() {
         fcs ^= byte;
     }
 
@@ -22,7 +22,7 @@ pub fn fcs(cmd_fcs_range: &str) -> Result<FcsBytes, ProtocolError> {
 }
 
 impl Display for FcsBytes {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{:X}{:X}", self.0, self.1)
     }
 }
@@ -33,4 +33,12 @@ impl FcsBytes {
     pub const fn value(self) -> u8 {
         self.1 + (self.0 * 10)
     }
+
+    /// Returns the FCS checksum as it appears in a frame: two uppercase hex digits.
+    #[must_use]
+    pub const fn digits(self) -> [u8; 2] {
+        const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+        [HEX[self.0 as usize], HEX[self.1 as usize]]
+    }
 }
@@ -1,10 +1,12 @@
-use super::ProtocolError;
-use crate::device::DeviceError;
+use super::buffer::{self, Buffer};
+use super::error::error_text;
+use super::{MessageRef, ProtocolError, DEFAULT_MAX_FRAME_LENGTH};
+use core::ops::{Deref, DerefMut};
+use core::str::FromStr;
 use derive_more::Display;
-use std::{
-    ops::{Deref, DerefMut},
-    str::FromStr,
-};
+
+/// Maximum length of a frame's params, which are stored in a fixed-capacity buffer.
+pub const MAX_PARAMS_LENGTH: usize = DEFAULT_MAX_FRAME_LENGTH - 9;
 
 /// A Hostlink command type.
 #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -73,19 +75,40 @@ pub enum MessageKind {
     ProgramWrite,
     #[display(fmt = "COMPOUND COMMAND")]
     CompoundCommand,
+    #[display(fmt = "FINS COMMAND")]
+    Fins,
+    /// Cancels a multi-frame exchange. The PLC doesn't respond.
+    #[display(fmt = "ABORT")]
+    Abort,
+    /// Resets the transmission control of every unit on the line. The PLC doesn't respond.
+    ///
+    /// Its frame is just `@**` and a carriage return: no node ID, FCS or `*` terminator.
+    #[display(fmt = "INITIALIZE")]
+    Initialize,
+    /// Sent by the PLC instead of a response when it doesn't support the header code of a command.
+    ///
+    /// Its frame has no end code, and doesn't say which command was rejected.
+    #[display(fmt = "UNDEFINED COMMAND")]
+    UndefinedCommand,
 }
 
-/// Stores a command's parameters as ASCII values.
+/// Stores a command's parameters as ASCII bytes.
+///
+/// At most [`MAX_PARAMS_LENGTH`] bytes can be stored, with or without `std`.
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct MessageParams(Vec<char>);
+pub struct MessageParams(Buffer<u8, MAX_PARAMS_LENGTH>);
 
 /// Represents a Node ID - i.e. a number between 0 and 99.
 #[derive(Debug, Display, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[display(fmt = "{:02}", "self.0")]
 pub struct NodeId(u8);
 
-/// A complete Hostlink command.
+/// A complete Hostlink frame, either a command or a response.
+///
+/// Use [`Request`](super::Request) and [`Response`](super::Response) where the direction is known;
+/// a response's end code is only separated from its data by [`Response`](super::Response).
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Message {
     /// Node ID
     node: NodeId,
@@ -112,6 +135,11 @@ impl Message {
         }
     }
 
+    #[must_use]
+    pub const fn node(&self) -> NodeId {
+        self.node
+    }
+
     #[must_use]
     pub const fn kind(&self) -> MessageKind {
         self.kind
@@ -122,84 +150,41 @@ impl Message {
         &self.params
     }
 
-    pub fn as_device_error(self) -> Result<DeviceError, ProtocolError> {
-        let string: String = self.params.iter().collect();
+    /// Serializes the command into a string that can be sent to a PLC.
+    #[cfg(feature = "std")]
+    pub fn serialize(self) -> Result<Box<str>, ProtocolError> {
+        let mut buffer = Vec::with_capacity(self.encoded_len());
+        self.encode_to_vec(&mut buffer)?;
 
-        DeviceError::try_from(string.as_str())
+        String::from_utf8(buffer)
+            .map(String::into_boxed_str)
+            .map_err(|_| ProtocolError::InvalidUtf8)
     }
 
+    /// Returns the length of the encoded frame.
     #[must_use]
-    pub fn check_device_error(&self) -> Option<DeviceError> {
-        self.clone().as_device_error().ok()?.to_result().err()
+    pub fn encoded_len(&self) -> usize {
+        MessageRef::from(self).encoded_len()
     }
 
-    /// Serializes the command into a string that can be sent to a PLC.
-    pub fn serialize(self) -> Result<Box<str>, ProtocolError> {
-        let mut buffer = String::with_capacity(10);
-
-        // begin
-        buffer.push('@');
-
-        // node number
-        buffer.push_str(&self.node.to_string());
-
-        // header code
-        buffer.push_str(self.kind.code());
-
-        // params
-        self.params.iter().for_each(|ch| buffer.push(*ch));
-
-        // FCS checksum
-        let fcs = super::fcs::fcs(&buffer)?;
-        buffer.push_str(&fcs.to_string());
-
-        // terminator
-        buffer.push_str("*\r");
+    /// Encodes the frame into the start of `buffer` and returns the number of bytes written.
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, ProtocolError> {
+        MessageRef::from(self).encode(buffer)
+    }
 
-        Ok(buffer.into_boxed_str())
+    /// Appends the encoded frame to `buffer`.
+    #[cfg(feature = "std")]
+    pub fn encode_to_vec(&self, buffer: &mut Vec<u8>) -> Result<(), ProtocolError> {
+        MessageRef::from(self).encode_to_vec(buffer)
     }
 
     pub fn set_node_id(&mut self, node: NodeId) {
         self.node = node;
     }
 
+    /// Parses a complete frame. See [`MessageRef::decode`] for parsing without allocating.
     pub fn parse(cmd: &str) -> Result<Self, ProtocolError> {
-        let mut cmd_iter = cmd.chars();
-
-        if cmd_iter.next() != Some('@') {
-            return Err(ProtocolError::MissingAtSymbol);
-        }
-
-        let node_id: u8 = cmd_iter
-            .next()
-            .zip(cmd_iter.next())
-            .map(|(first, last)| format!("{first}{last}"))
-            .ok_or(ProtocolError::MissingNodeId)?
-            .parse()?;
-        let node_id = NodeId::new(node_id)?;
-
-        let header_code_chars = cmd_iter
-            .next()
-            .zip(cmd_iter.next())
-            .map(|(first, last)| format!("{first}{last}"))
-            .ok_or(ProtocolError::MissingHeaderCode)?;
-
-        let command_kind = MessageKind::from_str(&header_code_chars)?;
-        let mut rest: String = cmd_iter.collect();
-
-        if rest.pop().zip(rest.pop()) != Some(('\r', '*')) {
-            return Err(ProtocolError::MissingTerminator);
-        }
-
-        // we won't store the FCS, but only chceck if it's there
-        rest.pop()
-            .zip(rest.pop())
-            .map(|(last, first)| format!("{first}{last}"))
-            .ok_or(ProtocolError::MissingFcs)?;
-
-        let params: Vec<char> = rest.chars().collect();
-
-        Ok(Self::new(node_id, command_kind, params.into()))
+        MessageRef::decode(cmd.as_bytes())?.to_message()
     }
 }
 
@@ -207,30 +192,46 @@ impl MessageParams {
     /// Creates an empty argument set.
     #[must_use]
     pub const fn new() -> Self {
-        Self(Vec::new())
+        Self(Buffer::new())
     }
-}
 
-impl From<Box<str>> for MessageParams {
-    fn from(value: Box<str>) -> Self {
-        Self(value.chars().collect())
+    /// Creates an argument set from ASCII bytes.
+    /// Fails if there are more than [`MAX_PARAMS_LENGTH`] bytes.
+    pub fn from_slice(params: &[u8]) -> Result<Self, ProtocolError> {
+        buffer::from_slice(params)
+            .map(Self)
+            .ok_or(ProtocolError::BufferTooSmall(params.len()))
+    }
+
+    /// Appends ASCII bytes.
+    /// Fails if the params would exceed [`MAX_PARAMS_LENGTH`] bytes.
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<(), ProtocolError> {
+        if buffer::extend(&mut self.0, bytes) == bytes.len() {
+            Ok(())
+        } else {
+            Err(ProtocolError::BufferTooSmall(self.0.len() + bytes.len()))
+        }
     }
 }
 
-impl From<&str> for MessageParams {
-    fn from(value: &str) -> Self {
-        Self(value.chars().collect())
+impl TryFrom<&[u8]> for MessageParams {
+    type Error = ProtocolError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_slice(value)
     }
 }
 
-impl From<Vec<char>> for MessageParams {
-    fn from(value: Vec<char>) -> Self {
-        Self(value)
+impl TryFrom<&str> for MessageParams {
+    type Error = ProtocolError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_slice(value.as_bytes())
     }
 }
 
 impl Deref for MessageParams {
-    type Target = [char];
+    type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
         &self.0
@@ -252,6 +253,45 @@ impl DerefMut for MessageParams {
 }
 
 impl MessageKind {
+    /// All command types.
+    pub const ALL: [Self; 35] = [
+        Self::IrSrAreaRead,
+        Self::LrAreaRead,
+        Self::HrAreaRead,
+        Self::PvRead,
+        Self::TcStatusRead,
+        Self::DmAreaRead,
+        Self::ArAreaRead,
+        Self::IrSrAreaWrite,
+        Self::LrAreaWrite,
+        Self::HrAreaWrite,
+        Self::PvWrite,
+        Self::TcStatusWrite,
+        Self::DmAreaWrite,
+        Self::ArAreaWrite,
+        Self::SvRead1,
+        Self::SvRead2,
+        Self::SvRead3,
+        Self::SvChange1,
+        Self::SvChange2,
+        Self::SvChange3,
+        Self::StatusRead,
+        Self::StatusWrite,
+        Self::ErrorRead,
+        Self::ForcedSet,
+        Self::ForcedReset,
+        Self::MultipleForcedSetReset,
+        Self::ForcedSetResetCancel,
+        Self::PcModelRead,
+        Self::Test,
+        Self::ProgramRead,
+        Self::ProgramWrite,
+        Self::CompoundCommand,
+        Self::Fins,
+        Self::Abort,
+        Self::Initialize,
+    ];
+
     /// Returns the command code.
     #[must_use]
     pub const fn code(self) -> &'static str {
@@ -288,8 +328,33 @@ impl MessageKind {
             Self::ProgramRead => "RP",
             Self::ProgramWrite => "WP",
             Self::CompoundCommand => "QQ",
+            Self::Fins => "FA",
+            Self::Abort => "XZ",
+            Self::Initialize => "**",
+            Self::UndefinedCommand => "IC",
         }
     }
+
+    /// Returns whether the PLC responds to this command.
+    /// [`Abort`](Self::Abort) and [`Initialize`](Self::Initialize) are never answered, and
+    /// [`UndefinedCommand`](Self::UndefinedCommand) is a response itself.
+    #[must_use]
+    pub const fn has_response(self) -> bool {
+        !matches!(
+            self,
+            Self::Abort | Self::Initialize | Self::UndefinedCommand
+        )
+    }
+
+    /// Returns the command type with the specified header code, including the
+    /// [`UndefinedCommand`](Self::UndefinedCommand) response.
+    #[must_use]
+    pub fn from_code(code: [u8; 2]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .chain([Self::UndefinedCommand])
+            .find(|kind| kind.code().as_bytes() == code)
+    }
 }
 
 impl FromStr for MessageKind {
@@ -329,7 +394,11 @@ impl FromStr for MessageKind {
             "RP" => Ok(Self::ProgramRead),
             "WP" => Ok(Self::ProgramWrite),
             "QQ" => Ok(Self::CompoundCommand),
-            _ => Err(ProtocolError::UnknownCommand(s.into())),
+            "FA" => Ok(Self::Fins),
+            "XZ" => Ok(Self::Abort),
+            "**" => Ok(Self::Initialize),
+            "IC" => Ok(Self::UndefinedCommand),
+            _ => Err(ProtocolError::UnknownCommand(error_text(s))),
         }
     }
 }
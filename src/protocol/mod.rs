@@ -1,11 +1,37 @@
+#[cfg(feature = "std")]
+mod address;
+mod buffer;
+mod codec;
+/// Commands with typed responses.
+#[cfg(feature = "std")]
+pub mod commands;
+mod decoder;
+mod device_error;
+#[cfg(feature = "std")]
 mod easy;
 mod error;
 /// FCS Checksum calculation and types.
 pub mod fcs;
 mod message;
+mod request;
 /// Response types.
 pub mod responses;
+#[cfg(feature = "serde")]
+mod serialization;
 
-pub use easy::EasyCommand;
-pub use error::Error as ProtocolError;
-pub use message::{Message, MessageKind, MessageParams, NodeId};
+#[cfg(feature = "std")]
+pub use address::{Address, Area};
+pub use buffer::Buffer;
+pub use codec::MessageRef;
+#[cfg(feature = "std")]
+pub use commands::Command;
+pub use decoder::{FrameDecoder, FrameError, DEFAULT_MAX_FRAME_LENGTH};
+pub use device_error::DeviceError;
+#[cfg(feature = "std")]
+pub use easy::{EasyCommand, TimerInstruction, MAX_READ_WORDS, MAX_TEST_LENGTH, MAX_WRITE_WORDS};
+pub use error::{Error as ProtocolError, ErrorText, MAX_ERROR_TEXT};
+pub use message::{Message, MessageKind, MessageParams, NodeId, MAX_PARAMS_LENGTH};
+pub use request::Request;
+pub use responses::Response;
+#[cfg(feature = "std")]
+pub use responses::ResponseData;
@@ -0,0 +1,68 @@
+use super::{Message, MessageKind, MessageParams, NodeId, ProtocolError};
+
+/// A command frame, sent from the host to a PLC.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Request {
+    /// Node ID
+    node: NodeId,
+    /// Command type
+    kind: MessageKind,
+    /// Arguments
+    params: MessageParams,
+}
+
+impl Request {
+    /// Creates a new command from the specified node ID and arguments.
+    #[must_use]
+    pub const fn new(node: NodeId, kind: MessageKind, params: MessageParams) -> Self {
+        Self { node, kind, params }
+    }
+
+    /// Creates a new command with no arguments from the specified node ID.
+    #[must_use]
+    pub const fn new_with_empty_params(node: NodeId, kind: MessageKind) -> Self {
+        Self::new(node, kind, MessageParams::new())
+    }
+
+    #[must_use]
+    pub const fn node(&self) -> NodeId {
+        self.node
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> MessageKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub const fn params(&self) -> &MessageParams {
+        &self.params
+    }
+
+    pub fn set_node_id(&mut self, node: NodeId) {
+        self.node = node;
+    }
+
+    /// Serializes the command into a string that can be sent to a PLC.
+    #[cfg(feature = "std")]
+    pub fn serialize(self) -> Result<Box<str>, ProtocolError> {
+        Message::from(self).serialize()
+    }
+
+    /// Parses a command frame.
+    pub fn parse(frame: &str) -> Result<Self, ProtocolError> {
+        Message::parse(frame).map(Self::from)
+    }
+}
+
+impl From<Message> for Request {
+    fn from(message: Message) -> Self {
+        Self::new(message.node(), message.kind(), message.params().clone())
+    }
+}
+
+impl From<Request> for Message {
+    fn from(request: Request) -> Self {
+        Self::new(request.node, request.kind, request.params)
+    }
+}
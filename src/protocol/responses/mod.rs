@@ -1,2 +1,209 @@
+/// Response types for the [`PcModelRead`](crate::protocol::MessageKind::PcModelRead) command.
+pub mod model;
 /// Response types for the [`StatusRead`](crate::protocol::MessageKind::StatusRead) command.
 pub mod status;
+/// Response types for area read commands.
+pub mod words;
+
+#[cfg(feature = "std")]
+use super::Area;
+use super::{DeviceError, Message, MessageKind, MessageParams, NodeId, ProtocolError};
+#[cfg(feature = "std")]
+use crate::fins::FinsResponse;
+#[cfg(feature = "std")]
+use model::PcModel;
+#[cfg(feature = "std")]
+use status::Status;
+#[cfg(feature = "std")]
+use words::{Words, WordsParseError};
+
+/// A response frame, sent from a PLC to the host, with its end code separated from the data.
+///
+/// [`Test`](MessageKind::Test) responses have no end code; their end code is always
+/// [`DeviceError::None`] and the echoed block of data is their data.
+///
+/// A [`DeviceError::UnsupportedCommand`] end code is sent as an
+/// [`UndefinedCommand`](MessageKind::UndefinedCommand) frame. That frame doesn't say which command
+/// was rejected, so parsing one gives `UnsupportedCommand(MessageKind::UndefinedCommand)`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Response {
+    /// Node ID
+    node: NodeId,
+    /// Type of the command this responds to
+    kind: MessageKind,
+    /// End code
+    end_code: DeviceError,
+    /// Data following the end code
+    data: MessageParams,
+}
+
+/// The decoded data of a [`Response`], depending on the command it responds to.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+// `Words` are stored inline, as in the protocol core without `std`
+#[allow(clippy::large_enum_variant)]
+pub enum ResponseData {
+    /// The block of data echoed by [`Test`](MessageKind::Test)
+    Echo(String),
+    /// The status returned by [`StatusRead`](MessageKind::StatusRead)
+    Status(Status),
+    /// The model returned by [`PcModelRead`](MessageKind::PcModelRead)
+    Model(PcModel),
+    /// Words returned by area reads, SV reads and [`ErrorRead`](MessageKind::ErrorRead)
+    Words(Words),
+    /// Completion flags returned by [`TcStatusRead`](MessageKind::TcStatusRead)
+    Flags(Vec<bool>),
+    /// The FINS response returned by [`Fins`](MessageKind::Fins)
+    Fins(FinsResponse),
+    /// Nothing, returned by writes and other commands which only report an end code
+    Empty,
+    /// Data of commands which aren't decoded, such as [`ProgramRead`](MessageKind::ProgramRead)
+    Raw(String),
+}
+
+impl Response {
+    /// Creates a new response.
+    #[must_use]
+    pub const fn new(
+        node: NodeId,
+        kind: MessageKind,
+        end_code: DeviceError,
+        data: MessageParams,
+    ) -> Self {
+        Self {
+            node,
+            kind,
+            end_code,
+            data,
+        }
+    }
+
+    #[must_use]
+    pub const fn node(&self) -> NodeId {
+        self.node
+    }
+
+    #[must_use]
+    pub const fn kind(&self) -> MessageKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub const fn end_code(&self) -> DeviceError {
+        self.end_code
+    }
+
+    /// Returns the data following the end code.
+    #[must_use]
+    pub const fn data(&self) -> &MessageParams {
+        &self.data
+    }
+
+    /// Returns the end code if it reports an error.
+    #[must_use]
+    pub fn error(&self) -> Option<DeviceError> {
+        self.end_code.to_result().err()
+    }
+
+    /// Serializes the response into a string that can be sent to the host.
+    #[cfg(feature = "std")]
+    pub fn serialize(self) -> Result<Box<str>, ProtocolError> {
+        Message::try_from(self)?.serialize()
+    }
+
+    /// Parses a response frame.
+    pub fn parse(frame: &str) -> Result<Self, ProtocolError> {
+        Message::parse(frame)?.try_into()
+    }
+
+    /// Decodes the data according to the kind of command this responds to.
+    /// Fails with the end code if it reports an error.
+    #[cfg(feature = "std")]
+    pub fn decode(&self) -> Result<ResponseData, ProtocolError> {
+        if let Some(error) = self.error() {
+            return Err(ProtocolError::Device(error));
+        }
+
+        let kind = self.kind;
+        let text = || String::from_utf8_lossy(&self.data).into_owned();
+
+        let data = match kind {
+            MessageKind::Test => ResponseData::Echo(text()),
+            MessageKind::StatusRead => ResponseData::Status(self.try_into()?),
+            MessageKind::PcModelRead => ResponseData::Model(self.try_into()?),
+            MessageKind::TcStatusRead => ResponseData::Flags(
+                self.data
+                    .iter()
+                    .map(|byte| match byte {
+                        b'0' => Ok(false),
+                        b'1' => Ok(true),
+                        _ => Err(WordsParseError::InvalidDigit(char::from(*byte))),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            MessageKind::Fins => ResponseData::Fins(FinsResponse::from_hostlink(self)?),
+            MessageKind::ErrorRead
+            | MessageKind::SvRead1
+            | MessageKind::SvRead2
+            | MessageKind::SvRead3 => ResponseData::Words(self.try_into()?),
+            _ if Area::ALL.iter().any(|area| area.read_kind() == kind) => {
+                ResponseData::Words(self.try_into()?)
+            }
+            MessageKind::ProgramRead | MessageKind::CompoundCommand => ResponseData::Raw(text()),
+            _ => ResponseData::Empty,
+        };
+
+        Ok(data)
+    }
+}
+
+impl TryFrom<Message> for Response {
+    type Error = ProtocolError;
+
+    /// Separates the end code from the data of a response frame.
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        let (end_code, data) = if message.kind() == MessageKind::Test {
+            (DeviceError::None, message.params().clone())
+        } else if message.kind() == MessageKind::UndefinedCommand {
+            (
+                DeviceError::UnsupportedCommand(MessageKind::UndefinedCommand),
+                MessageParams::default(),
+            )
+        } else {
+            let params = message.params();
+            let code = params.get(..2).ok_or(ProtocolError::ErrorCodeBadLength)?;
+
+            (
+                DeviceError::try_from((char::from(code[0]), char::from(code[1])))?,
+                MessageParams::from_slice(&params[2..])?,
+            )
+        };
+
+        Ok(Self::new(message.node(), message.kind(), end_code, data))
+    }
+}
+
+/// Fails if the end code and data don't fit into [`MessageParams`].
+impl TryFrom<Response> for Message {
+    type Error = ProtocolError;
+
+    fn try_from(response: Response) -> Result<Self, Self::Error> {
+        if let DeviceError::UnsupportedCommand(..) = response.end_code {
+            return Ok(Self::new(
+                response.node,
+                MessageKind::UndefinedCommand,
+                MessageParams::default(),
+            ));
+        }
+
+        let params = if response.kind == MessageKind::Test {
+            response.data
+        } else {
+            let mut params = MessageParams::from_slice(response.end_code.code().as_bytes())?;
+            params.extend_from_slice(&response.data)?;
+            params
+        };
+
+        Ok(Self::new(response.node, response.kind, params))
+    }
+}
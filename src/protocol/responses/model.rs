@@ -0,0 +1,85 @@
+use super::Response;
+use crate::protocol::Message;
+use thiserror::Error;
+
+/// The PLC model code returned by the [`PcModelRead`](crate::protocol::MessageKind::PcModelRead) command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PcModel(pub u8);
+
+/// An error that can occur while trying to parse `PcModel`.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum ModelParseError {
+    /// Message contains an error
+    #[error("Message contains an error")]
+    UnparsableMessage,
+    /// Missing model code
+    #[error("Missing model code")]
+    MissingCode,
+    /// Model code contains a non-hexadecimal character
+    #[error("Invalid hexadecimal digit: '{0}'")]
+    InvalidDigit(char),
+}
+
+impl TryFrom<&Response> for PcModel {
+    type Error = ModelParseError;
+
+    fn try_from(value: &Response) -> Result<Self, Self::Error> {
+        if value.error().is_some() {
+            return Err(Self::Error::UnparsableMessage);
+        }
+
+        let code = value.data().get(..2).ok_or(Self::Error::MissingCode)?;
+
+        code.iter()
+            .map(|&byte| char::from(byte))
+            .try_fold(Self(0), |model, ch| {
+                let digit = ch.to_digit(16).ok_or(Self::Error::InvalidDigit(ch))?;
+
+                #[allow(clippy::cast_possible_truncation)]
+                Ok(Self((model.0 << 4) | digit as u8))
+            })
+    }
+}
+
+impl TryFrom<Message> for PcModel {
+    type Error = ModelParseError;
+
+    fn try_from(value: Message) -> Result<Self, Self::Error> {
+        let response = Response::try_from(value).map_err(|_| Self::Error::UnparsableMessage)?;
+
+        Self::try_from(&response)
+    }
+}
+
+impl PcModel {
+    /// Returns the name of the model (or model family), if the code is known.
+    #[must_use]
+    pub const fn name(self) -> Option<&'static str> {
+        match self.0 {
+            0x01 => Some("C250"),
+            0x02 => Some("C500"),
+            0x03 => Some("C120"),
+            0x0E => Some("C2000"),
+            0x10 => Some("C1000H"),
+            0x11 => Some("C2000H/CQM1/CPM1"),
+            0x12 => Some("C20H/C28H/C40H/C200H/C200HS"),
+            0x20 => Some("CV500"),
+            0x21 => Some("CV1000"),
+            0x22 => Some("CV2000"),
+            0x30 => Some("CS/CJ"),
+            0x40 => Some("CVM1-CPU01"),
+            0x41 => Some("CVM1-CPU11"),
+            0x42 => Some("CVM1-CPU21"),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for PcModel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{name} ({:02X})", self.0),
+            None => write!(f, "Unknown ({:02X})", self.0),
+        }
+    }
+}
@@ -1,9 +1,11 @@
+use super::Response;
 use crate::protocol::Message;
 use derive_more::Display;
 use thiserror::Error;
 
 /// Represents the status of a PLC device.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Status {
     /// FALS *(Failure Alarm And Reset)* generated
     pub fals: bool,
@@ -17,6 +19,7 @@ pub struct Status {
 
 /// An operation mode.
 #[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatusMode {
     Program,
     Run,
@@ -25,6 +28,7 @@ pub enum StatusMode {
 
 /// Memory status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StatusMemory {
     /// Size of program memory in bytes (if available).
     pub size: Option<u16>,
@@ -50,34 +54,46 @@ pub enum StatusParseError {
     /// Memory size bits could not be mapped to any known memory size.
     #[error("Unexpected memory size bits: '{0}', '{1}', '{2}'")]
     UnknownMemorySize(bool, bool, bool),
+    /// Status data contains a non-hexadecimal character
+    #[error("Invalid hexadecimal digit: '{0}'")]
+    InvalidDigit(char),
 }
 
-impl TryFrom<Message> for Status {
+/// Decodes a byte sent as two hexadecimal digits.
+fn hex_byte(first: u8, second: u8) -> Result<u8, StatusParseError> {
+    let digit = |byte: u8| {
+        let ch = char::from(byte);
+        ch.to_digit(16).ok_or(StatusParseError::InvalidDigit(ch))
+    };
+
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(((digit(first)? << 4) | digit(second)?) as u8)
+}
+
+/// The mode and memory status bytes are each sent as two hexadecimal digits, e.g. `0210`.
+impl TryFrom<&Response> for Status {
     type Error = StatusParseError;
 
-    fn try_from(value: Message) -> Result<Self, Self::Error> {
-        if value.check_device_error().is_some() {
+    fn try_from(value: &Response) -> Result<Self, Self::Error> {
+        if value.error().is_some() {
             return Err(Self::Error::UnparsableMessage);
         }
 
-        // skip response code
-        let mut params_iter = value.params().iter().skip(2).map(|ch| *ch as u8);
+        let mut params_iter = value.data().iter();
+        let mut next_byte = || {
+            params_iter
+                .next()
+                .zip(params_iter.next())
+                .map(|(first, second)| hex_byte(*first, *second))
+        };
 
-        let mode_byte = params_iter
-            .next()
-            .zip(params_iter.next())
-            .map(|(first, second)| (first & 0b1111_0000) | (second & 0b0000_1111))
-            .ok_or(Self::Error::MissingMode)?;
+        let mode_byte = next_byte().ok_or(Self::Error::MissingMode)??;
 
         let fals = (mode_byte & 0b1000_0000) > 0;
         let error = (mode_byte & 0b0001_0000) > 0;
         let mode = StatusMode::parse(mode_byte)?;
 
-        let memory = params_iter
-            .next()
-            .zip(params_iter.next())
-            .map(|(first, second)| (first & 0b1111_0000) | (second & 0b0000_1111))
-            .ok_or(Self::Error::MissingMemory)?;
+        let memory = next_byte().ok_or(Self::Error::MissingMemory)??;
         let memory = StatusMemory::parse(memory)?;
 
         Ok(Self {
@@ -89,21 +105,38 @@ impl TryFrom<Message> for Status {
     }
 }
 
+impl TryFrom<Message> for Status {
+    type Error = StatusParseError;
+
+    fn try_from(value: Message) -> Result<Self, Self::Error> {
+        let response = Response::try_from(value).map_err(|_| Self::Error::UnparsableMessage)?;
+
+        Self::try_from(&response)
+    }
+}
+
+impl Status {
+    /// Encodes the status the same way a PLC does in its [`StatusRead`](crate::protocol::MessageKind::StatusRead) response
+    /// (without the response code).
+    #[must_use]
+    #[cfg(feature = "std")]
+    pub fn encode(&self) -> String {
+        let mode_byte = (u8::from(self.fals) << 7) | (u8::from(self.error) << 4) | self.mode.bits();
+
+        format!("{mode_byte:02X}{:02X}", self.memory.byte())
+    }
+}
+
 impl StatusMode {
     /// Parse the operation mode from a byte obtained using a [`StatusRead`](crate::protocol::MessageKind::StatusRead) command.
     /// # Example
     /// ```rust
     /// use hostlink::protocol::responses::status::StatusMode;
     ///
-    /// // These are the bytes you'd get from a StatusRead command if the PLC is in RUN mode.
-    /// let first_byte = 0b0011_0000;
-    /// let second_byte = 0b0011_0010;
-    ///
-    /// // We need to create a single byte by using the first byte as the first 4 bits
-    /// // and the second byte as the next 4 bits.
-    /// let mode_byte = (first_byte & 0b1111_0000) | (second_byte & 0b0000_1111);
+    /// // The mode byte is sent as two hexadecimal digits.
+    /// // These are the digits you'd get from a StatusRead command if the PLC is in RUN mode.
+    /// let mode_byte = u8::from_str_radix("02", 16).unwrap();
     ///
-    /// assert_eq!(mode_byte, 0b0011_0010 /* or 50 / 0x32 */);
     /// let status = StatusMode::parse(mode_byte).unwrap();
     ///
     /// assert_eq!(status, StatusMode::Run);
@@ -119,6 +152,37 @@ impl StatusMode {
             _ => Err(StatusParseError::UnknownMode(first, second)),
         }
     }
+
+    /// Returns the mode data sent with a [`StatusWrite`](crate::protocol::MessageKind::StatusWrite) command.
+    #[must_use]
+    pub const fn write_code(self) -> &'static str {
+        match self {
+            Self::Program => "00",
+            Self::Monitor => "02",
+            Self::Run => "03",
+        }
+    }
+
+    /// Parses the mode data sent with a [`StatusWrite`](crate::protocol::MessageKind::StatusWrite) command.
+    #[must_use]
+    pub fn from_write_code(code: &str) -> Option<Self> {
+        match code {
+            "00" => Some(Self::Program),
+            "02" => Some(Self::Monitor),
+            "03" => Some(Self::Run),
+            _ => None,
+        }
+    }
+
+    /// Returns the mode bits, as they appear in the status byte.
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        match self {
+            Self::Program => 0b0000_0000,
+            Self::Run => 0b0000_0010,
+            Self::Monitor => 0b0000_0011,
+        }
+    }
 }
 
 impl StatusMemory {
@@ -142,4 +206,21 @@ impl StatusMemory {
             write_protection,
         })
     }
+
+    /// Returns the memory status byte.
+    #[must_use]
+    pub const fn byte(self) -> u8 {
+        let size_bits = match self.size {
+            Some(4000) => 0b0001_0000,
+            Some(8000) => 0b0010_0000,
+            Some(7200) => 0b0100_0000,
+            _ => 0b0000_0000,
+        };
+
+        if self.write_protection {
+            size_bits
+        } else {
+            size_bits | 0b0000_1000
+        }
+    }
 }
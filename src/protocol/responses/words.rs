@@ -0,0 +1,73 @@
+use super::Response;
+use crate::protocol::{Buffer, Message, MAX_PARAMS_LENGTH};
+use thiserror::Error;
+
+/// Maximum number of words in a response.
+pub const MAX_WORDS: usize = MAX_PARAMS_LENGTH / 4;
+
+/// Data words returned by an area read command, such as [`DmAreaRead`](crate::protocol::MessageKind::DmAreaRead).
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Words(pub Buffer<u16, MAX_WORDS>);
+
+/// An error that can occur while trying to parse `Words`.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum WordsParseError {
+    /// Message contains an error
+    #[error("Message contains an error")]
+    UnparsableMessage,
+    /// Data length is not a multiple of 4 characters
+    #[error("Data length must be a multiple of 4, got {0}")]
+    BadLength(usize),
+    /// A word contains a non-hexadecimal character
+    #[error("Invalid hexadecimal digit: '{0}'")]
+    InvalidDigit(char),
+}
+
+impl TryFrom<&Response> for Words {
+    type Error = WordsParseError;
+
+    fn try_from(value: &Response) -> Result<Self, Self::Error> {
+        if value.error().is_some() {
+            return Err(Self::Error::UnparsableMessage);
+        }
+
+        let data = value.data();
+
+        if !data.len().is_multiple_of(4) {
+            return Err(Self::Error::BadLength(data.len()));
+        }
+
+        data.chunks(4)
+            .map(|word| {
+                word.iter()
+                    .map(|&byte| char::from(byte))
+                    .try_fold(0u16, |acc, ch| {
+                        let digit = ch.to_digit(16).ok_or(Self::Error::InvalidDigit(ch))?;
+
+                        #[allow(clippy::cast_possible_truncation)]
+                        Ok((acc << 4) | digit as u16)
+                    })
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl TryFrom<Message> for Words {
+    type Error = WordsParseError;
+
+    fn try_from(value: Message) -> Result<Self, Self::Error> {
+        let response = Response::try_from(value).map_err(|_| Self::Error::UnparsableMessage)?;
+
+        Self::try_from(&response)
+    }
+}
+
+impl Words {
+    /// Encodes data words as command parameters (4 hexadecimal digits per word).
+    #[must_use]
+    #[cfg(feature = "std")]
+    pub fn encode(words: &[u16]) -> String {
+        words.iter().map(|word| format!("{word:04X}")).collect()
+    }
+}
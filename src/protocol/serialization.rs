@@ -0,0 +1,210 @@
+//! `serde` support for types whose wire representation differs from their Rust layout.
+
+use super::{DeviceError, MessageKind, MessageParams, NodeId};
+use core::fmt;
+use serde::de::{self, Deserializer, Unexpected, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+
+/// Serialized as a number, validated on deserialization.
+impl Serialize for NodeId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(**self)
+    }
+}
+
+impl<'de> Deserialize<'de> for NodeId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::new(u8::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// Serialized as the header code, e.g. `"RD"`.
+impl Serialize for MessageKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HeaderCode;
+
+        impl Visitor<'_> for HeaderCode {
+            type Value = MessageKind;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a header code")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                match *value.as_bytes() {
+                    [first, second] => MessageKind::from_code([first, second]),
+                    _ => None,
+                }
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(HeaderCode)
+    }
+}
+
+/// Serialized as a string, or as bytes if the params aren't valid UTF-8.
+impl Serialize for MessageParams {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match core::str::from_utf8(self) {
+            Ok(params) => serializer.serialize_str(params),
+            Err(_) => serializer.serialize_bytes(self),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageParams {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Params;
+
+        impl<'de> Visitor<'de> for Params {
+            type Value = MessageParams;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string or bytes of params")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                self.visit_bytes(value.as_bytes())
+            }
+
+            fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+                MessageParams::from_slice(value).map_err(E::custom)
+            }
+
+            /// Bytes end up as a sequence in formats without a bytes type, e.g. JSON.
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut params = MessageParams::new();
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    params
+                        .extend_from_slice(&[byte])
+                        .map_err(de::Error::custom)?;
+                }
+
+                Ok(params)
+            }
+        }
+
+        // only self-describing formats can tell a string from bytes
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Params)
+        } else {
+            deserializer.deserialize_bytes(Params)
+        }
+    }
+}
+
+/// Serialized as `{"code": "13", "description": "FCS error"}`.
+/// [`UnsupportedCommand`](DeviceError::UnsupportedCommand) has code `"IC"` and also a `"command"`
+/// with the header code of the rejected command. The description is ignored on deserialization.
+/// Binary formats always get all three fields, `command` being `None` for other end codes.
+impl Serialize for DeviceError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Description<'a>(&'a DeviceError);
+
+        impl Serialize for Description<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self.0)
+            }
+        }
+
+        let command = match self {
+            DeviceError::UnsupportedCommand(kind) => Some(kind),
+            _ => None,
+        };
+
+        // the number of fields is fixed for formats which don't write their names
+        let human_readable = serializer.is_human_readable();
+        let mut state = serializer.serialize_struct("DeviceError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("description", &Description(self))?;
+        if command.is_none() && human_readable {
+            state.skip_field("command")?;
+        } else {
+            state.serialize_field("command", &command)?;
+        }
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceError {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// `None` for `"IC"`, whose error depends on the command.
+        struct EndCode(Option<DeviceError>);
+
+        impl<'de> Deserialize<'de> for EndCode {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct Code;
+
+                impl Visitor<'_> for Code {
+                    type Value = EndCode;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str("an end code")
+                    }
+
+                    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                        if value == "IC" {
+                            return Ok(EndCode(None));
+                        }
+
+                        DeviceError::try_from(value)
+                            .map(|error| EndCode(Some(error)))
+                            .map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+                    }
+                }
+
+                deserializer.deserialize_str(Code)
+            }
+        }
+
+        /// The description, which is read and ignored.
+        #[derive(Default)]
+        struct Description;
+
+        impl<'de> Deserialize<'de> for Description {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct Text;
+
+                impl Visitor<'_> for Text {
+                    type Value = Description;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str("a description")
+                    }
+
+                    fn visit_str<E: de::Error>(self, _value: &str) -> Result<Self::Value, E> {
+                        Ok(Description)
+                    }
+                }
+
+                deserializer.deserialize_str(Text)
+            }
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename = "DeviceError")]
+        struct Repr {
+            code: EndCode,
+            #[serde(default, rename = "description")]
+            _description: Description,
+            #[serde(default)]
+            command: Option<MessageKind>,
+        }
+
+        let repr = Repr::deserialize(deserializer)?;
+
+        match (repr.code.0, repr.command) {
+            (Some(error), _) => Ok(error),
+            (None, Some(kind)) => Ok(DeviceError::UnsupportedCommand(kind)),
+            (None, None) => Err(de::Error::missing_field("command")),
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use crate::device::{
+    transport::{Reply, Responder},
+    DeviceError, Transport,
+};
+use crate::protocol::{
+    responses::{
+        status::{Status, StatusMode},
+        words::Words,
+    },
+    Address, Area, FrameDecoder, MessageKind, MessageParams, NodeId, ProtocolError, Request,
+    Response,
+};
+use std::{io, str::FromStr};
+
+/// Implements the PLC side of Hostlink commands.
+///
+/// Every method corresponds to one or more [`MessageKind`]s. Methods which aren't implemented
+/// report [`InstructionNotFound`](DeviceError::InstructionNotFound) (except [`test`](Self::test),
+/// which echoes the data back by default). Returned errors are sent back as end codes, except
+/// [`UnsupportedCommand`](DeviceError::UnsupportedCommand), which is sent as an
+/// [`UndefinedCommand`](MessageKind::UndefinedCommand) frame like an older PLC model would.
+pub trait Handler {
+    /// Handles a [`Test`](MessageKind::Test) command. Returns the block of data to send back.
+    fn test(&mut self, data: &str) -> String {
+        data.into()
+    }
+
+    /// Handles a [`StatusRead`](MessageKind::StatusRead) command.
+    fn status_read(&mut self) -> Result<Status, DeviceError> {
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles a [`StatusWrite`](MessageKind::StatusWrite) command.
+    fn status_write(&mut self, mode: StatusMode) -> Result<(), DeviceError> {
+        let _ = mode;
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles an area read command, such as [`DmAreaRead`](MessageKind::DmAreaRead).
+    fn area_read(&mut self, area: Area, start: u16, count: u16) -> Result<Vec<u16>, DeviceError> {
+        let _ = (area, start, count);
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles an area write command, such as [`DmAreaWrite`](MessageKind::DmAreaWrite).
+    fn area_write(&mut self, area: Area, start: u16, data: &[u16]) -> Result<(), DeviceError> {
+        let _ = (area, start, data);
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles a [`TcStatusRead`](MessageKind::TcStatusRead) command.
+    fn tc_status_read(&mut self, start: u16, count: u16) -> Result<Vec<bool>, DeviceError> {
+        let _ = (start, count);
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles a [`TcStatusWrite`](MessageKind::TcStatusWrite) command.
+    fn tc_status_write(&mut self, start: u16, flags: &[bool]) -> Result<(), DeviceError> {
+        let _ = (start, flags);
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles a [`PcModelRead`](MessageKind::PcModelRead) command. Returns the model code.
+    fn model_read(&mut self) -> Result<u8, DeviceError> {
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles an [`ErrorRead`](MessageKind::ErrorRead) command. Returns the error words.
+    fn error_read(&mut self, clear: bool) -> Result<Vec<u16>, DeviceError> {
+        let _ = clear;
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles a [`ForcedSet`](MessageKind::ForcedSet) or [`ForcedReset`](MessageKind::ForcedReset) command.
+    fn forced_set_reset(&mut self, address: Address, set: bool) -> Result<(), DeviceError> {
+        let _ = (address, set);
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles a [`ForcedSetResetCancel`](MessageKind::ForcedSetResetCancel) command.
+    fn forced_cancel(&mut self) -> Result<(), DeviceError> {
+        Err(DeviceError::InstructionNotFound)
+    }
+
+    /// Handles any other command. Returns the response data (without the end code).
+    fn other(&mut self, request: &Request) -> Result<String, DeviceError> {
+        let _ = request;
+        Err(DeviceError::InstructionNotFound)
+    }
+}
+
+impl<H: Handler + ?Sized> Handler for &mut H {
+    fn test(&mut self, data: &str) -> String {
+        (**self).test(data)
+    }
+
+    fn status_read(&mut self) -> Result<Status, DeviceError> {
+        (**self).status_read()
+    }
+
+    fn status_write(&mut self, mode: StatusMode) -> Result<(), DeviceError> {
+        (**self).status_write(mode)
+    }
+
+    fn area_read(&mut self, area: Area, start: u16, count: u16) -> Result<Vec<u16>, DeviceError> {
+        (**self).area_read(area, start, count)
+    }
+
+    fn area_write(&mut self, area: Area, start: u16, data: &[u16]) -> Result<(), DeviceError> {
+        (**self).area_write(area, start, data)
+    }
+
+    fn tc_status_read(&mut self, start: u16, count: u16) -> Result<Vec<bool>, DeviceError> {
+        (**self).tc_status_read(start, count)
+    }
+
+    fn tc_status_write(&mut self, start: u16, flags: &[bool]) -> Result<(), DeviceError> {
+        (**self).tc_status_write(start, flags)
+    }
+
+    fn model_read(&mut self) -> Result<u8, DeviceError> {
+        (**self).model_read()
+    }
+
+    fn error_read(&mut self, clear: bool) -> Result<Vec<u16>, DeviceError> {
+        (**self).error_read(clear)
+    }
+
+    fn forced_set_reset(&mut self, address: Address, set: bool) -> Result<(), DeviceError> {
+        (**self).forced_set_reset(address, set)
+    }
+
+    fn forced_cancel(&mut self) -> Result<(), DeviceError> {
+        (**self).forced_cancel()
+    }
+
+    fn other(&mut self, request: &Request) -> Result<String, DeviceError> {
+        (**self).other(request)
+    }
+}
+
+/// The slave side of a Hostlink link: parses incoming commands, passes them to a [`Handler`]
+/// and sends back the responses.
+///
+/// The server can either run on its own transport (see [`serve`](Self::serve)),
+/// or be used as a [`Responder`] of a [`MemoryTransport`](crate::device::transport::MemoryTransport).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostlinkServer<H> {
+    node: NodeId,
+    handler: H,
+}
+
+impl<H: Handler> HostlinkServer<H> {
+    /// Creates a server which answers commands sent to `node`.
+    pub const fn new(node: NodeId, handler: H) -> Self {
+        Self { node, handler }
+    }
+
+    pub const fn node(&self) -> NodeId {
+        self.node
+    }
+
+    pub const fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    pub fn into_handler(self) -> H {
+        self.handler
+    }
+
+    /// Answers commands received over `transport`, until the transport is closed.
+    /// Read timeouts are ignored.
+    pub fn serve<T: Transport>(&mut self, mut transport: T) -> io::Result<()> {
+        let mut decoder = FrameDecoder::new();
+        let mut chunk = [0; 256];
+
+        loop {
+            // noise and broken frames are ignored, like a real PLC would
+            while let Some(frame) = decoder.next_frame() {
+                if let Ok(frame) = frame {
+                    let response = self.respond(frame).bytes;
+
+                    transport.write_all(&response)?;
+                    transport.flush()?;
+                }
+            }
+
+            match transport.read(&mut chunk) {
+                Ok(0) => return Ok(()),
+                Ok(count) => {
+                    decoder.push(&chunk[..count]);
+                }
+                Err(error) if error.kind() == io::ErrorKind::TimedOut => (),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Handles a single frame and returns the response frame (if any).
+    /// Frames addressed to other nodes and unparsable frames are ignored, like a real PLC would.
+    pub fn handle_frame(&mut self, frame: &str) -> Option<String> {
+        if frame.get(1..3) != Some(self.node.to_string().as_str()) {
+            return None;
+        }
+
+        trace!(node = %self.node, frame = %frame.escape_debug(), "request received");
+
+        let request = match Request::parse(frame) {
+            Ok(request) => request,
+            Err(ProtocolError::UnknownHeaderCode(..)) => {
+                debug!(node = %self.node, "unknown header code");
+                let kind = MessageKind::UndefinedCommand;
+
+                return self.response(kind, Err(DeviceError::UnsupportedCommand(kind)));
+            }
+            Err(ProtocolError::FcsMismatch { .. }) => {
+                debug!(node = %self.node, "request FCS mismatch");
+                let kind = MessageKind::from_str(frame.get(3..5)?).ok()?;
+
+                return self.response(kind, Err(DeviceError::FCSError));
+            }
+            Err(_error) => {
+                debug!(node = %self.node, error = %_error, "malformed request ignored");
+                return None;
+            }
+        };
+
+        let kind = request.kind();
+
+        // ABORT and INITIALIZE are never answered
+        if !kind.has_response() {
+            debug!(node = %self.node, kind = kind.code(), "transmission control command");
+            return None;
+        }
+
+        let result = if kind == MessageKind::Test {
+            let data = String::from_utf8_lossy(request.params());
+            Ok(self.handler.test(&data))
+        } else {
+            self.execute(&request).inspect_err(|_error| {
+                debug!(node = %self.node, kind = kind.code(), error = %_error, "request failed");
+            })
+        };
+
+        self.response(kind, result)
+    }
+
+    fn response(&self, kind: MessageKind, result: Result<String, DeviceError>) -> Option<String> {
+        let (end_code, data) = match result {
+            Ok(data) => (DeviceError::None, data),
+            Err(error) => (error, String::new()),
+        };
+
+        let data = MessageParams::from_slice(data.as_bytes()).ok()?;
+
+        Response::new(self.node, kind, end_code, data)
+            .serialize()
+            .ok()
+            .map(String::from)
+    }
+
+    fn execute(&mut self, request: &Request) -> Result<String, DeviceError> {
+        let params = String::from_utf8_lossy(request.params());
+        let kind = request.kind();
+
+        if !params.is_ascii() {
+            return Err(DeviceError::EntryNumberData);
+        }
+
+        if let Some(area) = Area::ALL.into_iter().find(|area| area.read_kind() == kind) {
+            let (start, count) = start_and_count(&params)?;
+
+            return Ok(Words::encode(&self.handler.area_read(area, start, count)?));
+        }
+
+        if let Some(area) = Area::ALL.into_iter().find(|area| area.write_kind() == kind) {
+            let start = number(params.get(..4).ok_or(DeviceError::FormatError)?)?;
+            let data = hex_words(&params[4..])?;
+
+            return self
+                .handler
+                .area_write(area, start, &data)
+                .map(|()| String::new());
+        }
+
+        match kind {
+            MessageKind::StatusRead => self.handler.status_read().map(|status| status.encode()),
+            MessageKind::StatusWrite => {
+                let mode =
+                    StatusMode::from_write_code(&params).ok_or(DeviceError::EntryNumberData)?;
+
+                self.handler.status_write(mode).map(|()| String::new())
+            }
+            MessageKind::PcModelRead => self
+                .handler
+                .model_read()
+                .map(|model| format!("{model:02X}")),
+            MessageKind::ErrorRead => {
+                let clear = match &*params {
+                    "00" => false,
+                    "01" => true,
+                    _ => return Err(DeviceError::EntryNumberData),
+                };
+
+                self.handler
+                    .error_read(clear)
+                    .map(|errors| Words::encode(&errors))
+            }
+            MessageKind::TcStatusRead => {
+                let (start, count) = start_and_count(&params)?;
+
+                self.handler.tc_status_read(start, count).map(|flags| {
+                    flags
+                        .iter()
+                        .map(|flag| if *flag { '1' } else { '0' })
+                        .collect()
+                })
+            }
+            MessageKind::TcStatusWrite => {
+                let start = number(params.get(..4).ok_or(DeviceError::FormatError)?)?;
+                let flags = params[4..]
+                    .chars()
+                    .map(|ch| match ch {
+                        '0' => Ok(false),
+                        '1' => Ok(true),
+                        _ => Err(DeviceError::EntryNumberData),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                self.handler
+                    .tc_status_write(start, &flags)
+                    .map(|()| String::new())
+            }
+            MessageKind::ForcedSet | MessageKind::ForcedReset => {
+                let address = forced_address(&params)?;
+
+                self.handler
+                    .forced_set_reset(address, kind == MessageKind::ForcedSet)
+                    .map(|()| String::new())
+            }
+            MessageKind::ForcedSetResetCancel => {
+                self.handler.forced_cancel().map(|()| String::new())
+            }
+            _ => self.handler.other(request),
+        }
+    }
+}
+
+impl<H: Handler> Responder for HostlinkServer<H> {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        std::str::from_utf8(frame)
+            .ok()
+            .and_then(|frame| self.handle_frame(frame))
+            .map(String::into_bytes)
+            .unwrap_or_default()
+            .into()
+    }
+}
+
+/// Parses a 4 digit decimal number. Anything but digits (e.g. a sign) is a format error.
+fn number(digits: &str) -> Result<u16, DeviceError> {
+    if digits.len() != 4 || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(DeviceError::FormatError);
+    }
+
+    digits.parse().map_err(|_| DeviceError::EntryNumberData)
+}
+
+/// Parses the begin word and number of words of a read command.
+fn start_and_count(params: &str) -> Result<(u16, u16), DeviceError> {
+    if params.len() != 8 || !params.is_ascii() {
+        return Err(DeviceError::FormatError);
+    }
+
+    Ok((number(&params[..4])?, number(&params[4..])?))
+}
+
+/// Parses words sent as 4 hexadecimal digits each.
+fn hex_words(data: &str) -> Result<Vec<u16>, DeviceError> {
+    if data.is_empty()
+        || !data.len().is_multiple_of(4)
+        || !data.bytes().all(|byte| byte.is_ascii_hexdigit())
+    {
+        return Err(DeviceError::FormatError);
+    }
+
+    (0..data.len())
+        .step_by(4)
+        .map(|i| u16::from_str_radix(&data[i..i + 4], 16).map_err(|_| DeviceError::EntryNumberData))
+        .collect()
+}
+
+/// Parses the operand, word and bit of a forced set/reset command.
+fn forced_address(params: &str) -> Result<Address, DeviceError> {
+    if params.len() != 10 || !params.is_ascii() {
+        return Err(DeviceError::FormatError);
+    }
+
+    let area = Area::from_operand(&params[..4]).ok_or(DeviceError::EntryNumberData)?;
+    let word = number(&params[4..8])?;
+    let bit = &params[8..];
+    if !bit.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(DeviceError::FormatError);
+    }
+    let bit = bit.parse().map_err(|_| DeviceError::EntryNumberData)?;
+
+    Address::bit(area, word, bit).map_err(|_| DeviceError::EntryNumberData)
+}
@@ -0,0 +1,159 @@
+use crate::device::{
+    transport::{Reply, Responder},
+    DeviceError,
+};
+use crate::protocol::{fcs::fcs, NodeId};
+use std::{collections::HashMap, time::Duration};
+
+/// A way to misbehave when answering a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// Replaces a digit of the FCS with another hex digit, so it no longer matches the frame.
+    CorruptFcs,
+    /// Sends only the first `n` bytes of the response.
+    Truncate(usize),
+    /// Leaves out the `*\r` terminator.
+    NoTerminator,
+    /// Sends the response after a delay.
+    Delay(Duration),
+    /// Doesn't respond at all.
+    NoReply,
+    /// Responds on behalf of another node.
+    WrongNode(NodeId),
+    /// Responds with the specified end code instead of the actual response.
+    EndCode(DeviceError),
+}
+
+/// A [`Responder`] which answers requests using another responder (such as a [`SimulatedPlc`](super::SimulatedPlc)),
+/// but misbehaves on selected requests.
+///
+/// Requests are numbered from zero, in the order they are received.
+/// # Example
+/// ```rust
+/// use hostlink::{
+///     device::{transport::MemoryTransport, PlcDevice},
+///     protocol::NodeId,
+///     sim::{Fault, FaultInjector, SimulatedPlc},
+/// };
+///
+/// let node = NodeId::new(0).unwrap();
+///
+/// // The first response will have a broken FCS
+/// let responder = FaultInjector::new(SimulatedPlc::new(node)).on_request(0, Fault::CorruptFcs);
+/// let mut device = PlcDevice::with_transport(Box::new(MemoryTransport::new(responder)), node);
+///
+/// assert!(device.test().is_err());
+/// assert!(device.test().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaultInjector<R> {
+    inner: R,
+    script: HashMap<usize, Vec<Fault>>,
+    always: Vec<Fault>,
+    requests: usize,
+}
+
+impl<R: Responder> FaultInjector<R> {
+    /// Wraps a responder without any faults.
+    #[must_use]
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            script: HashMap::new(),
+            always: Vec::new(),
+            requests: 0,
+        }
+    }
+
+    /// Adds a fault to the response to request number `request`.
+    /// Multiple faults can be applied to the same response.
+    #[must_use]
+    pub fn on_request(mut self, request: usize, fault: Fault) -> Self {
+        self.script.entry(request).or_default().push(fault);
+        self
+    }
+
+    /// Adds a fault to every response.
+    #[must_use]
+    pub fn always(mut self, fault: Fault) -> Self {
+        self.always.push(fault);
+        self
+    }
+
+    /// Returns the number of requests received so far.
+    #[must_use]
+    pub const fn requests(&self) -> usize {
+        self.requests
+    }
+
+    #[must_use]
+    pub const fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+}
+
+impl<R: Responder> Responder for FaultInjector<R> {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        let mut reply = self.inner.respond(frame);
+        let faults = self.script.remove(&self.requests).unwrap_or_default();
+        self.requests += 1;
+
+        for fault in self.always.iter().chain(&faults) {
+            apply(*fault, frame, &mut reply);
+        }
+
+        reply
+    }
+}
+
+fn apply(fault: Fault, request: &[u8], reply: &mut Reply) {
+    let bytes = &mut reply.bytes;
+
+    match fault {
+        Fault::CorruptFcs => {
+            // swapped for another hex digit, so the frame is still received whole
+            if let Some(index) = bytes.len().checked_sub(4) {
+                bytes[index] = if bytes[index] == b'0' { b'1' } else { b'0' };
+            }
+        }
+        Fault::Truncate(length) => bytes.truncate(length),
+        Fault::NoTerminator => {
+            if bytes.ends_with(b"*\r") {
+                bytes.truncate(bytes.len() - 2);
+            }
+        }
+        Fault::Delay(delay) => reply.delay += delay,
+        Fault::NoReply => bytes.clear(),
+        Fault::WrongNode(node) => {
+            if bytes.len() >= 3 {
+                bytes.splice(1..3, node.to_string().into_bytes());
+                refresh_fcs(bytes);
+            }
+        }
+        Fault::EndCode(error) => {
+            if let Some(header) = request.get(..5) {
+                *bytes = [header, error.code().as_bytes(), b"00*\r"].concat();
+                refresh_fcs(bytes);
+            }
+        }
+    }
+}
+
+/// Recalculates the FCS of a complete frame.
+fn refresh_fcs(frame: &mut [u8]) {
+    let Some(end) = frame.len().checked_sub(4) else {
+        return;
+    };
+
+    let fcs = std::str::from_utf8(&frame[..end])
+        .ok()
+        .and_then(|range| fcs(range).ok());
+
+    if let Some(fcs) = fcs {
+        frame[end..end + 2].copy_from_slice(fcs.to_string().as_bytes());
+    }
+}
@@ -0,0 +1,241 @@
+mod faults;
+
+use crate::device::{
+    transport::{Reply, Responder},
+    DeviceError,
+};
+use crate::protocol::{
+    responses::status::{Status, StatusMemory, StatusMode},
+    Address, Area, NodeId,
+};
+use crate::server::{Handler, HostlinkServer};
+use std::collections::{BTreeSet, HashMap};
+
+pub use faults::{Fault, FaultInjector};
+
+/// Number of timers/counters, whose completion flags can be accessed with TC status commands.
+const TC_COUNT: u16 = 512;
+
+/// A simulated PLC, which answers Hostlink commands the same way a C200H-series PLC would.
+///
+/// The simulator is a [`Handler`], so it can also be served over a real transport using a [`HostlinkServer`].
+/// Connect it to a [`PlcDevice`](crate::device::PlcDevice) using a [`MemoryTransport`](crate::device::transport::MemoryTransport).
+/// # Example
+/// ```rust
+/// use hostlink::{
+///     device::{transport::MemoryTransport, PlcDevice},
+///     protocol::{Area, NodeId},
+///     sim::SimulatedPlc,
+/// };
+///
+/// let node = NodeId::new(0).unwrap();
+///
+/// let mut plc = SimulatedPlc::new(node);
+/// plc.write(Area::Dm, 100, &[0x1234]).unwrap();
+///
+/// let transport = MemoryTransport::new(plc);
+/// let mut device = PlcDevice::with_transport(Box::new(transport), node);
+///
+/// assert_eq!(device.read_words(Area::Dm, 100, 1).unwrap(), vec![0x1234]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulatedPlc {
+    node: NodeId,
+    status: Status,
+    model: u8,
+    memory: HashMap<Area, Vec<u16>>,
+    tc_status: Vec<bool>,
+    forced: BTreeSet<Address>,
+    errors: [u16; 2],
+}
+
+impl SimulatedPlc {
+    /// Model code of C200H PLCs, as returned by [`PcModelRead`](MessageKind::PcModelRead).
+    pub const MODEL_C200H: u8 = 0x12;
+
+    /// Creates a simulated C200H with cleared memory, in PROGRAM mode.
+    #[must_use]
+    pub fn new(node: NodeId) -> Self {
+        Self {
+            node,
+            status: Status {
+                fals: false,
+                error: false,
+                mode: StatusMode::Program,
+                memory: StatusMemory {
+                    size: Some(8000),
+                    write_protection: false,
+                },
+            },
+            model: Self::MODEL_C200H,
+            memory: Area::ALL
+                .into_iter()
+                .map(|area| (area, vec![0; area.size().into()]))
+                .collect(),
+            tc_status: vec![false; TC_COUNT.into()],
+            forced: BTreeSet::new(),
+            errors: [0; 2],
+        }
+    }
+
+    /// Sets the model code returned by [`PcModelRead`](MessageKind::PcModelRead).
+    #[must_use]
+    pub const fn with_model(mut self, model: u8) -> Self {
+        self.model = model;
+        self
+    }
+
+    #[must_use]
+    pub const fn node(&self) -> NodeId {
+        self.node
+    }
+
+    #[must_use]
+    pub const fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn set_status(&mut self, status: Status) {
+        self.status = status;
+    }
+
+    pub fn set_mode(&mut self, mode: StatusMode) {
+        self.status.mode = mode;
+    }
+
+    #[must_use]
+    pub const fn model(&self) -> u8 {
+        self.model
+    }
+
+    /// Returns the error words reported by [`ErrorRead`](MessageKind::ErrorRead).
+    #[must_use]
+    pub const fn errors(&self) -> [u16; 2] {
+        self.errors
+    }
+
+    pub fn set_errors(&mut self, errors: [u16; 2]) {
+        self.errors = errors;
+    }
+
+    /// Returns the addresses of all forced bits.
+    #[must_use]
+    pub const fn forced(&self) -> &BTreeSet<Address> {
+        &self.forced
+    }
+
+    /// Reads `count` words from `area`, starting at word `start`.
+    pub fn read(&self, area: Area, start: u16, count: u16) -> Result<&[u16], DeviceError> {
+        self.memory[&area]
+            .get(start.into()..usize::from(start) + usize::from(count))
+            .ok_or(DeviceError::AddressOver)
+    }
+
+    /// Writes words into `area`, starting at word `start`.
+    pub fn write(&mut self, area: Area, start: u16, data: &[u16]) -> Result<(), DeviceError> {
+        self.memory
+            .get_mut(&area)
+            .and_then(|memory| memory.get_mut(start.into()..usize::from(start) + data.len()))
+            .ok_or(DeviceError::AddressOver)?
+            .copy_from_slice(data);
+
+        Ok(())
+    }
+
+    /// Sets or resets a single bit.
+    pub fn write_bit(&mut self, address: Address, value: bool) -> Result<(), DeviceError> {
+        let bit = address.bit.ok_or(DeviceError::EntryNumberData)?;
+        let word = self
+            .memory
+            .get_mut(&address.area)
+            .and_then(|memory| memory.get_mut(usize::from(address.word)))
+            .ok_or(DeviceError::AddressOver)?;
+
+        if value {
+            *word |= 1 << bit;
+        } else {
+            *word &= !(1 << bit);
+        }
+
+        Ok(())
+    }
+
+    const fn check_writable(&self) -> Result<(), DeviceError> {
+        match self.status.mode {
+            StatusMode::Run => Err(DeviceError::NotExecutableInRunMode),
+            StatusMode::Monitor | StatusMode::Program => Ok(()),
+        }
+    }
+}
+
+impl Handler for SimulatedPlc {
+    fn status_read(&mut self) -> Result<Status, DeviceError> {
+        Ok(self.status)
+    }
+
+    fn status_write(&mut self, mode: StatusMode) -> Result<(), DeviceError> {
+        self.status.mode = mode;
+        Ok(())
+    }
+
+    fn area_read(&mut self, area: Area, start: u16, count: u16) -> Result<Vec<u16>, DeviceError> {
+        self.read(area, start, count).map(<[u16]>::to_vec)
+    }
+
+    fn area_write(&mut self, area: Area, start: u16, data: &[u16]) -> Result<(), DeviceError> {
+        self.check_writable()?;
+        self.write(area, start, data)
+    }
+
+    fn tc_status_read(&mut self, start: u16, count: u16) -> Result<Vec<bool>, DeviceError> {
+        self.tc_status
+            .get(start.into()..usize::from(start) + usize::from(count))
+            .map(<[bool]>::to_vec)
+            .ok_or(DeviceError::AddressOver)
+    }
+
+    fn tc_status_write(&mut self, start: u16, flags: &[bool]) -> Result<(), DeviceError> {
+        self.check_writable()?;
+        self.tc_status
+            .get_mut(start.into()..usize::from(start) + flags.len())
+            .ok_or(DeviceError::AddressOver)?
+            .copy_from_slice(flags);
+
+        Ok(())
+    }
+
+    fn model_read(&mut self) -> Result<u8, DeviceError> {
+        Ok(self.model)
+    }
+
+    fn error_read(&mut self, clear: bool) -> Result<Vec<u16>, DeviceError> {
+        let errors = self.errors.to_vec();
+
+        if clear {
+            self.errors = [0; 2];
+        }
+
+        Ok(errors)
+    }
+
+    fn forced_set_reset(&mut self, address: Address, set: bool) -> Result<(), DeviceError> {
+        self.check_writable()?;
+        self.write_bit(address, set)?;
+        self.forced.insert(address);
+
+        Ok(())
+    }
+
+    fn forced_cancel(&mut self) -> Result<(), DeviceError> {
+        self.check_writable()?;
+        self.forced.clear();
+
+        Ok(())
+    }
+}
+
+impl Responder for SimulatedPlc {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        HostlinkServer::new(self.node, self).respond(frame)
+    }
+}
@@ -0,0 +1,25 @@
+//! Wrappers around the `tracing` macros, which expand to nothing unless the `tracing` feature is enabled.
+//!
+//! Raw frames are logged at trace level, transaction summaries at debug level.
+
+/// Enters a debug-level span until the end of the enclosing block.
+macro_rules! debug_span {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = ::tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($arg)*);
+    };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::trace!($($arg)*);
+    };
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "std")]
+
+use hostlink::protocol::{Address, Area, ProtocolError};
+
+#[test]
+fn address_word() {
+    let address: Address = "DM100".parse().unwrap();
+
+    assert_eq!(address, Address::word(Area::Dm, 100));
+    assert_eq!(address.to_string(), "DM0100");
+}
+
+#[test]
+fn address_bit() {
+    let address: Address = "ir001.03".parse().unwrap();
+
+    assert_eq!(address, Address::bit(Area::IrSr, 1, 3).unwrap());
+    assert_eq!(address.to_string(), "IR0001.03");
+}
+
+#[test]
+fn address_invalid() {
+    assert_eq!(
+        "HR5.16".parse::<Address>(),
+        Err(ProtocolError::InvalidAddress("HR5.16".try_into().unwrap()))
+    );
+    assert!("XX10".parse::<Address>().is_err());
+    assert!("DM".parse::<Address>().is_err());
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "std")]
+
+use hostlink::analyzer::{Analyzer, Direction, Issue};
+use std::time::{Duration, SystemTime};
+
+#[test]
+fn analyzer_pairs_commands_and_responses() {
+    let start = SystemTime::now();
+    let mut analyzer = Analyzer::new();
+
+    let mut frames = analyzer.feed(b"@00RD0000000254*\r@00RD00", start);
+    frames.extend(analyzer.feed(b"0000000056*\r", start + Duration::from_millis(5)));
+    frames.extend(analyzer.feed(
+        b"@00TS!rust!47*\r@00TS!rust!47*\r",
+        start + Duration::from_millis(50),
+    ));
+
+    let directions: Vec<Direction> = frames.iter().map(|frame| frame.direction).collect();
+    assert_eq!(
+        directions,
+        [
+            Direction::Command,
+            Direction::Response,
+            Direction::Command,
+            Direction::Response
+        ]
+    );
+    assert_eq!(frames[1].describe(), "0000 0000");
+    assert_eq!(frames[1].timestamp, start);
+    assert_eq!(frames[2].gap, Some(Duration::from_millis(45)));
+    assert!(frames.iter().all(|frame| frame.issues.is_empty()));
+}
+
+#[test]
+fn analyzer_flags_broken_frames() {
+    let start = SystemTime::now();
+    let mut analyzer = Analyzer::new().with_gap(Duration::from_millis(20));
+
+    let frames = analyzer.feed(b"@00MS5F*\r@00M", start);
+    assert_eq!(frames[0].issues, [Issue::FcsMismatch]);
+    assert_eq!(frames[0].direction, Direction::Unknown);
+
+    assert!(analyzer.idle(start + Duration::from_millis(10)).is_none());
+
+    let incomplete = analyzer.idle(start + Duration::from_millis(30)).unwrap();
+    assert_eq!(incomplete.raw, "@00M");
+    assert_eq!(incomplete.issues, [Issue::Incomplete]);
+}
+
+#[test]
+fn analyzer_flags_timing() {
+    let start = SystemTime::now();
+    let mut analyzer = Analyzer::new().with_latency_limit(Duration::from_millis(100));
+
+    // A command that is retried after a timeout, then answered late
+    let mut frames = analyzer.feed(b"@00MS5E*\r", start);
+    frames.extend(analyzer.feed(b"@00MS5E*\r", start + Duration::from_secs(1)));
+    frames.extend(analyzer.feed(b"@00MS00002854*\r", start + Duration::from_millis(1200)));
+
+    assert_eq!(frames[1].direction, Direction::Command);
+    assert_eq!(frames[1].issues, [Issue::MissingResponse]);
+    assert_eq!(frames[2].direction, Direction::Response);
+    assert_eq!(
+        frames[2].issues,
+        [Issue::SlowResponse(Duration::from_millis(200))]
+    );
+}
+
+#[test]
+fn analyzer_bounded() {
+    let start = SystemTime::now();
+    let mut analyzer = Analyzer::new();
+
+    // a line that never sends `@` or a terminator is dropped as noise, not buffered forever
+    let frames = analyzer.feed(&[b'x'; 5000], start);
+    assert!(!frames.is_empty());
+    assert!(frames
+        .iter()
+        .all(|frame| frame.issues == [Issue::Malformed] && frame.raw.len() <= 2048));
+    assert!(analyzer.idle(start + Duration::from_secs(1)).is_none());
+
+    // an oversized frame is dropped up to its terminator, the next one is decoded again
+    let mut bytes = vec![b'@'];
+    bytes.extend_from_slice(&[b'0'; 3000]);
+    bytes.extend_from_slice(b"\r@00MS5E*\r");
+    let frames = analyzer.feed(&bytes, start);
+
+    assert_eq!(frames.last().unwrap().direction, Direction::Command);
+    assert!(frames.last().unwrap().issues.is_empty());
+    assert!(frames[..frames.len() - 1]
+        .iter()
+        .all(|frame| frame.direction == Direction::Unknown));
+}
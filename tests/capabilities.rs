@@ -0,0 +1,111 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, DeviceError, Error, PlcDevice},
+    protocol::{responses::model::PcModel, Area, MessageKind, NodeId},
+    server::{Handler, HostlinkServer},
+};
+
+/// An older model without PC MODEL READ, the AR area read command or an LR area.
+struct OldModel;
+
+impl Handler for OldModel {
+    fn model_read(&mut self) -> Result<u8, DeviceError> {
+        Err(DeviceError::UnsupportedCommand(MessageKind::PcModelRead))
+    }
+
+    fn area_read(&mut self, area: Area, _start: u16, count: u16) -> Result<Vec<u16>, DeviceError> {
+        match area {
+            Area::Ar => Err(DeviceError::UnsupportedCommand(MessageKind::ArAreaRead)),
+            Area::Lr => Err(DeviceError::AddressOver),
+            // says nothing about the area
+            Area::Tc => Err(DeviceError::NotExecutableInRunMode),
+            _ => Ok(vec![0; usize::from(count)]),
+        }
+    }
+
+    fn area_write(&mut self, _area: Area, _start: u16, _data: &[u16]) -> Result<(), DeviceError> {
+        Ok(())
+    }
+}
+
+/// A newer model which supports everything.
+struct NewModel;
+
+impl Handler for NewModel {
+    fn model_read(&mut self) -> Result<u8, DeviceError> {
+        Ok(0x30)
+    }
+
+    fn area_read(&mut self, _area: Area, _start: u16, count: u16) -> Result<Vec<u16>, DeviceError> {
+        Ok(vec![0; usize::from(count)])
+    }
+}
+
+fn connect<H: Handler + Send + 'static>(handler: H) -> PlcDevice {
+    let node = NodeId::new(1).unwrap();
+    let server = HostlinkServer::new(node, handler);
+
+    PlcDevice::with_transport(Box::new(MemoryTransport::new(server)), node)
+}
+
+#[test]
+fn capabilities_probe() {
+    let mut device = connect(OldModel);
+    assert!(device.capabilities().is_none());
+
+    let capabilities = device.probe_capabilities().unwrap();
+    assert_eq!(capabilities.model(), None);
+    assert_eq!(
+        capabilities.unsupported().collect::<Vec<_>>(),
+        vec![
+            MessageKind::LrAreaRead,
+            MessageKind::ArAreaRead,
+            MessageKind::LrAreaWrite,
+            MessageKind::ArAreaWrite,
+            MessageKind::PcModelRead,
+        ]
+    );
+    assert!(capabilities.supports_area(Area::Dm));
+    assert!(capabilities.supports_area(Area::Tc));
+    assert!(!capabilities.supports_area(Area::Lr));
+
+    let mut device = connect(NewModel);
+    let capabilities = device.probe_capabilities().unwrap();
+    assert_eq!(capabilities.model(), Some(PcModel(0x30)));
+    assert_eq!(capabilities.unsupported().count(), 0);
+}
+
+#[test]
+fn capabilities_fail_fast() {
+    let mut device = connect(OldModel);
+    device.probe_capabilities().unwrap();
+    let sent = device.stats().snapshot().total().requests;
+
+    assert!(matches!(
+        device.write_words(Area::Ar, 0, &[1]),
+        Err(Error::NotSupported(MessageKind::ArAreaWrite))
+    ));
+    assert!(matches!(
+        device.model(),
+        Err(Error::NotSupported(MessageKind::PcModelRead))
+    ));
+    assert_eq!(
+        Error::NotSupported(MessageKind::PcModelRead).to_string(),
+        "PC MODEL READ is not supported on this model"
+    );
+    assert_eq!(device.stats().snapshot().total().requests, sent);
+
+    // supported commands are still sent
+    device.write_words(Area::Dm, 0, &[1]).unwrap();
+    assert_eq!(device.stats().snapshot().total().requests, sent + 1);
+}
+
+#[test]
+fn capabilities_forgotten_on_node_change() {
+    let mut device = connect(OldModel);
+    device.probe_capabilities().unwrap();
+
+    device.set_node_id(NodeId::new(2).unwrap());
+    assert!(device.capabilities().is_none());
+}
@@ -0,0 +1,199 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    capture::{
+        CaptureError, CaptureReader, CaptureWriter, Direction, Record, Replay, ReplayTransport,
+    },
+    device::{transport::MemoryTransport, Error, PlcDevice},
+    protocol::{Area, NodeId, ProtocolError},
+    sim::{Fault, FaultInjector, SimulatedPlc},
+};
+use std::{
+    io::Write,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+fn temp_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("hostlink-{}-{name}.hlcap", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    path
+}
+
+#[test]
+fn capture_round_trip() {
+    let path = temp_file("round-trip");
+    let record = |direction, bytes: &[u8]| Record {
+        direction,
+        timestamp: SystemTime::UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456),
+        bytes: bytes.to_vec(),
+    };
+    let records = [
+        record(Direction::Sent, b"@00MS5E*\r"),
+        record(Direction::Received, b"@00MS00002854*\r"),
+        record(Direction::Received, b"@00M"),
+    ];
+
+    // the second writer appends without writing another header
+    let mut writer = CaptureWriter::append(&path).unwrap();
+    writer.write(&records[0]).unwrap();
+    drop(writer);
+
+    let mut writer = CaptureWriter::append(&path).unwrap();
+    writer.write(&records[1]).unwrap();
+    writer.write(&records[2]).unwrap();
+    drop(writer);
+
+    let read: Vec<Record> = CaptureReader::open(&path)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, records);
+
+    assert!(matches!(
+        CaptureReader::new(&b"@00MS5E*\r"[..]),
+        Err(CaptureError::BadMagic)
+    ));
+
+    let mut data = std::fs::read(&path).unwrap();
+    data.pop();
+    let mut reader = CaptureReader::new(data.as_slice()).unwrap();
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_ok());
+    assert!(matches!(reader.next(), Some(Err(CaptureError::Truncated))));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn capture_append_checks_header() {
+    let path = temp_file("append-header");
+
+    // appending to something else must not corrupt it
+    std::fs::write(&path, b"@00MS5E*\r").unwrap();
+    assert!(matches!(
+        CaptureWriter::append(&path),
+        Err(CaptureError::BadMagic)
+    ));
+    assert_eq!(std::fs::read(&path).unwrap(), b"@00MS5E*\r");
+
+    std::fs::write(&path, b"HLCAP\x02").unwrap();
+    assert!(matches!(
+        CaptureWriter::append(&path),
+        Err(CaptureError::UnsupportedVersion(2))
+    ));
+
+    // a record cut short by a crash is removed before appending
+    std::fs::remove_file(&path).unwrap();
+    let record = |bytes: &[u8]| Record {
+        direction: Direction::Sent,
+        timestamp: SystemTime::UNIX_EPOCH,
+        bytes: bytes.to_vec(),
+    };
+    let mut writer = CaptureWriter::append(&path).unwrap();
+    writer.write(&record(b"@00MS5E*\r")).unwrap();
+    drop(writer);
+
+    let mut data = std::fs::read(&path).unwrap();
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    std::fs::write(&path, data).unwrap();
+
+    let mut writer = CaptureWriter::append(&path).unwrap();
+    writer.write(&record(b"@00RD")).unwrap();
+    drop(writer);
+
+    let read: Vec<Record> = CaptureReader::open(&path)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, [record(b"@00MS5E*\r"), record(b"@00RD")]);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+/// A disk which fills up after the capture's header.
+struct DiskFull(usize);
+
+impl Write for DiskFull {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        if self.0 < buffer.len() {
+            return Err(std::io::Error::other("disk full"));
+        }
+        self.0 -= buffer.len();
+
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn capture_failure_keeps_transaction() {
+    let node = NodeId::new(5).unwrap();
+    let mut plc = SimulatedPlc::new(node);
+    plc.write(Area::Dm, 200, &[1, 2, 3]).unwrap();
+
+    let mut device = PlcDevice::with_transport(Box::new(MemoryTransport::new(plc)), node);
+    device.set_capture(Some(CaptureWriter::new(DiskFull(6)).unwrap()));
+
+    // the command is sent once and its response kept, only the recording stops
+    device.write_words(Area::Dm, 200, &[4]).unwrap();
+    assert!(!device.is_capturing());
+    assert_eq!(device.stats().snapshot().total().requests, 1);
+    assert_eq!(device.read_words(Area::Dm, 200, 3).unwrap(), [4, 2, 3]);
+}
+
+#[test]
+fn capture_and_replay() {
+    let path = temp_file("replay");
+    let node = NodeId::new(5).unwrap();
+
+    let mut plc = SimulatedPlc::new(node);
+    plc.write(Area::Dm, 200, &[1, 2, 3]).unwrap();
+    let responder = FaultInjector::new(plc).on_request(1, Fault::CorruptFcs);
+    let transport = MemoryTransport::new(responder).with_timeout(Duration::from_millis(20));
+
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+    device.set_capture(Some(CaptureWriter::append(&path).unwrap()));
+    assert_eq!(device.read_words(Area::Dm, 200, 3).unwrap(), [1, 2, 3]);
+    assert!(device.status().is_err());
+    drop(device);
+
+    // the field problem can now be reproduced without the PLC
+    let transport = ReplayTransport::new(Replay::open(&path).unwrap());
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+    assert_eq!(device.read_words(Area::Dm, 200, 3).unwrap(), [1, 2, 3]);
+    assert!(matches!(
+        device.status(),
+        Err(Error::Protocol(ProtocolError::FcsMismatch { .. }))
+    ));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn replay_timing() {
+    let start = SystemTime::now();
+    let record = |direction, offset, bytes: &[u8]| Record {
+        direction,
+        timestamp: start + Duration::from_millis(offset),
+        bytes: bytes.to_vec(),
+    };
+
+    // the first response arrived too late, the retry was answered in time
+    let replay = Replay::new([
+        record(Direction::Sent, 0, b"@00MS5E*\r"),
+        record(Direction::Received, 100, b"@00MS00002854*\r"),
+        record(Direction::Sent, 200, b"@00MS5E*\r"),
+        record(Direction::Received, 205, b"@00MS00002854*\r"),
+    ])
+    .with_timing();
+    let transport = ReplayTransport::new(replay).with_timeout(Duration::from_millis(50));
+    let mut device = PlcDevice::with_transport(Box::new(transport), NodeId::new(0).unwrap());
+
+    assert!(matches!(device.status(), Err(Error::Io(_))));
+    assert!(device.status().is_ok());
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "cli")]
+
+use hostlink::{
+    cli::{load_tags, parse_node, Poller, Tag},
+    device::{transport::MemoryTransport, PlcDevice},
+    protocol::{Address, Area, NodeId},
+    sim::SimulatedPlc,
+};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn cli_tag() {
+    assert_eq!(
+        "tank_level 3 DM0100".parse(),
+        Ok(Tag {
+            name: "tank_level".into(),
+            node: NodeId::new(3).unwrap(),
+            address: Address::word(Area::Dm, 100),
+        })
+    );
+    assert_eq!(
+        "pump.running\t0   IR001.03".parse::<Tag>().unwrap().address,
+        Address::bit(Area::IrSr, 1, 3).unwrap()
+    );
+
+    assert!("tank_level 0".parse::<Tag>().is_err());
+    assert!("tank_level 0 DM0100 extra".parse::<Tag>().is_err());
+    assert!("tank{level} 0 DM0100".parse::<Tag>().is_err());
+    assert!("tank_level 100 DM0100".parse::<Tag>().is_err());
+    assert!("tank_level 0 XX0100".parse::<Tag>().is_err());
+
+    assert_eq!(parse_node("31"), Ok(NodeId::new(31).unwrap()));
+    assert!(parse_node("node").is_err());
+}
+
+#[test]
+fn cli_tag_list() {
+    let path = std::env::temp_dir().join(format!("hostlink-{}-tags.txt", std::process::id()));
+
+    std::fs::write(
+        &path,
+        "# level\ntank_level 0 DM0100\n\n  valve 1 HR0005.02\n",
+    )
+    .unwrap();
+    let tags = load_tags(&path).unwrap();
+    assert_eq!(
+        tags.iter().map(|tag| tag.name.as_str()).collect::<Vec<_>>(),
+        ["tank_level", "valve"]
+    );
+
+    // errors name the line
+    std::fs::write(&path, "tank_level 0 DM0100\n\nvalve 1\n").unwrap();
+    let error = load_tags(&path).unwrap_err();
+    assert!(
+        error.ends_with(":3: expected `name node address`"),
+        "{error}"
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(load_tags(&path).is_err());
+}
+
+#[test]
+fn cli_metrics() {
+    let node = NodeId::new(0).unwrap();
+    let plc = Arc::new(Mutex::new(SimulatedPlc::new(node)));
+    plc.lock().unwrap().write(Area::Dm, 100, &[1234]).unwrap();
+    let mut device = PlcDevice::with_transport(Box::new(MemoryTransport::new(plc)), node);
+
+    let tags = ["tank_level 0 DM0100", "valve 0 HR9999", "other 5 DM0000"]
+        .into_iter()
+        .map(|line| line.parse().unwrap())
+        .collect();
+    let mut poller = Poller::new(tags);
+    let mut errors = Vec::new();
+    let metrics = poller.poll(&mut device, |node, error| {
+        errors.push((node, error.to_string()));
+    });
+
+    for line in [
+        "# TYPE hostlink_up gauge",
+        "hostlink_up{node=\"00\"} 1",
+        "hostlink_plc_mode{node=\"00\",mode=\"PROGRAM\"} 1",
+        "hostlink_plc_fals{node=\"00\"} 0",
+        "hostlink_plc_error{node=\"00\"} 0",
+        "hostlink_tag_value{tag=\"tank_level\",node=\"00\",address=\"DM0100\"} 1234",
+        "# TYPE hostlink_comm_failures_total counter",
+        "hostlink_up{node=\"05\"} 0",
+        "hostlink_comm_failures_total{node=\"00\"} 1",
+        "hostlink_comm_failures_total{node=\"05\"} 2",
+    ] {
+        assert!(
+            metrics.lines().any(|metric| metric == line),
+            "{line} missing from\n{metrics}"
+        );
+    }
+
+    assert_eq!(
+        errors
+            .iter()
+            .map(|(node, _)| node.to_string())
+            .collect::<Vec<_>>(),
+        ["00", "05", "05"]
+    );
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "std")]
+
+use hostlink::protocol::{Message, MessageKind, MessageRef, NodeId, ProtocolError};
+
+#[test]
+fn codec_encode() {
+    let node = NodeId::new(12).unwrap();
+    let message = Message::new(
+        node,
+        MessageKind::DmAreaRead,
+        "01000002".try_into().unwrap(),
+    );
+    let frame = MessageRef::new(node, MessageKind::DmAreaRead, b"01000002");
+
+    let mut buffer = [0; 32];
+    let length = frame.encode(&mut buffer).unwrap();
+
+    assert_eq!(length, frame.encoded_len());
+    assert_eq!(&buffer[..length], message.serialize().unwrap().as_bytes());
+    assert_eq!(
+        frame.encode(&mut buffer[..length - 1]),
+        Err(ProtocolError::BufferTooSmall(length))
+    );
+
+    // encoding appends to what's already there
+    let mut vec = b"xyz".to_vec();
+    frame.encode_to_vec(&mut vec).unwrap();
+
+    assert_eq!(&vec[3..], &buffer[..length]);
+}
+
+#[test]
+fn codec_decode_borrows() {
+    let received = b"@05RD0012340ABC27*\r".to_vec();
+    let frame = MessageRef::decode(&received).unwrap();
+
+    assert_eq!(frame.node(), NodeId::new(5).unwrap());
+    assert_eq!(frame.kind(), MessageKind::DmAreaRead);
+    assert_eq!(frame.params(), b"0012340ABC");
+    assert!(received.as_ptr_range().contains(&frame.params().as_ptr()));
+
+    assert_eq!(
+        frame.to_message().unwrap(),
+        Message::parse("@05RD0012340ABC27*\r").unwrap()
+    );
+}
+
+#[test]
+fn codec_decode_errors() {
+    assert_eq!(
+        MessageRef::decode(b"05RD00*\r"),
+        Err(ProtocolError::MissingAtSymbol)
+    );
+    assert_eq!(
+        MessageRef::decode(b"@05RD00"),
+        Err(ProtocolError::MissingTerminator)
+    );
+    assert_eq!(
+        MessageRef::decode(b"@05RD*\r"),
+        Err(ProtocolError::MissingFcs)
+    );
+    assert!(matches!(
+        MessageRef::decode(b"@05RD0012340ABC00*\r"),
+        Err(ProtocolError::FcsMismatch {
+            received: ('0', '0'),
+            ..
+        })
+    ));
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, DeviceError, PlcDevice},
+    protocol::{
+        commands::{ReadArea, ReadStatus, ReadSv, ReadTcStatus, WriteTcStatus},
+        Area, Command, EasyCommand, MessageKind, NodeId, ProtocolError, Response, ResponseData,
+        TimerInstruction,
+    },
+    sim::SimulatedPlc,
+};
+use std::sync::{Arc, Mutex};
+
+fn connect() -> (Arc<Mutex<SimulatedPlc>>, PlcDevice) {
+    let node = NodeId::new(1).unwrap();
+    let plc = Arc::new(Mutex::new(SimulatedPlc::new(node)));
+    let device = PlcDevice::with_transport(Box::new(MemoryTransport::new(plc.clone())), node);
+
+    (plc, device)
+}
+
+#[test]
+fn command_execute_typed() {
+    let (plc, mut device) = connect();
+    plc.lock()
+        .unwrap()
+        .write(Area::Dm, 100, &[1, 2, 3])
+        .unwrap();
+
+    let words: Vec<u16> = device
+        .execute(&ReadArea::new(Area::Dm, 100, 3).unwrap())
+        .unwrap();
+    assert_eq!(words, [1, 2, 3]);
+
+    device
+        .execute(&WriteTcStatus::new(4, &[true, false]).unwrap())
+        .unwrap();
+    let flags: Vec<bool> = device.execute(&ReadTcStatus::new(4, 2).unwrap()).unwrap();
+    assert_eq!(flags, [true, false]);
+
+    let status = device.execute(&ReadStatus).unwrap();
+    assert_eq!(status, plc.lock().unwrap().status());
+}
+
+#[test]
+fn command_execute_untyped() {
+    let (_, mut device) = connect();
+
+    let data = device
+        .execute(&EasyCommand::make_test("hello").unwrap())
+        .unwrap();
+    assert_eq!(data, ResponseData::Echo("hello".into()));
+}
+
+#[test]
+fn command_decode() {
+    let node = NodeId::new(1).unwrap();
+    let response = |end_code, data: &str| {
+        Response::new(
+            node,
+            MessageKind::SvRead1,
+            end_code,
+            data.try_into().unwrap(),
+        )
+    };
+
+    assert_eq!(
+        ReadSv::decode(&response(DeviceError::None, "0150")),
+        Ok(150)
+    );
+    assert!(ReadSv::decode(&response(DeviceError::None, "01A0")).is_err());
+    assert_eq!(
+        ReadSv::decode(&response(DeviceError::AddressOver, "")),
+        Err(ProtocolError::Device(DeviceError::AddressOver))
+    );
+
+    let command = ReadSv::new(TimerInstruction::Tim, 1, None)
+        .unwrap()
+        .command();
+    assert_eq!(command.kind(), MessageKind::SvRead1);
+}
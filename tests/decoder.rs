@@ -0,0 +1,99 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{
+        transport::{MemoryTransport, Reply, Responder},
+        PlcDevice,
+    },
+    protocol::{EasyCommand, FrameDecoder, FrameError, MessageKind, NodeId, ProtocolError},
+    sim::SimulatedPlc,
+};
+
+/// Sends line noise and a broken frame before every response.
+struct Noisy(SimulatedPlc);
+
+impl Responder for Noisy {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        let mut bytes = b"\0\xFF@00R".to_vec();
+        bytes.extend(self.0.respond(frame).bytes);
+        bytes.into()
+    }
+}
+
+#[test]
+fn decoder_chunks() {
+    let mut decoder = FrameDecoder::new();
+
+    for &byte in b"@00TS123447*" {
+        decoder.push(&[byte]);
+        assert_eq!(decoder.next_frame(), None);
+    }
+
+    decoder.push(b"\r@00TSAB44*\r@01");
+
+    assert_eq!(decoder.next_frame(), Some(Ok(&b"@00TS123447*\r"[..])));
+
+    let message = decoder.next_message().unwrap().unwrap();
+    assert_eq!(message.kind(), MessageKind::Test);
+    assert_eq!(message.params(), b"AB");
+
+    assert_eq!(decoder.next_frame(), None);
+    assert_eq!(decoder.pending(), b"@01");
+
+    decoder.clear();
+    assert_eq!(decoder.pending(), b"");
+}
+
+#[test]
+fn decoder_resync() {
+    let mut decoder = FrameDecoder::new().with_max_length(16);
+
+    decoder.push(b"xx@00RD@00TS1234");
+    assert_eq!(decoder.next_frame(), Some(Err(FrameError::Noise(2))));
+    assert_eq!(decoder.next_frame(), Some(Err(FrameError::Unterminated(5))));
+    assert_eq!(decoder.next_frame(), None);
+
+    // the rest of a frame that's too long is dropped as well
+    decoder.push(b"5678901234");
+    assert_eq!(decoder.next_frame(), Some(Err(FrameError::TooLong(16))));
+    decoder.push(b"5678*\r@00TS47*\r");
+    assert_eq!(decoder.next_frame(), Some(Ok(&b"@00TS47*\r"[..])));
+    assert_eq!(decoder.next_frame(), None);
+
+    // so a test command can't carry an `@`, the frame would be split
+    assert_eq!(
+        EasyCommand::make_test("user@plc"),
+        Err(ProtocolError::InvalidTestData)
+    );
+    assert!(EasyCommand::make_test("a*b, c!").is_ok());
+}
+
+#[test]
+fn device_skips_noise() {
+    let node = NodeId::new(0).unwrap();
+    let transport = MemoryTransport::new(Noisy(SimulatedPlc::new(node)));
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+
+    device.test().unwrap();
+    assert!(device.status().is_ok());
+}
+
+#[cfg(feature = "tokio-util")]
+#[test]
+fn decoder_codec() {
+    use hostlink::protocol::Message;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    let message = Message::new(
+        NodeId::new(0).unwrap(),
+        MessageKind::Test,
+        "AB".try_into().unwrap(),
+    );
+    let mut codec = FrameDecoder::new();
+    let mut bytes = bytes::BytesMut::from(&b"\0"[..]);
+
+    codec.encode(message.clone(), &mut bytes).unwrap();
+
+    assert_eq!(codec.decode(&mut bytes).unwrap(), Some(message));
+    assert_eq!(codec.decode(&mut bytes).unwrap(), None);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use hostlink::protocol::{Message, MessageKind, MessageParams, NodeId};
 
 #[test]
@@ -19,7 +21,7 @@ fn deserialize_2() {
     let original = Message::new(
         NodeId::new(65).unwrap(),
         MessageKind::DmAreaRead,
-        vec!['0', 'a'].into(),
+        "0a".try_into().unwrap(),
     );
 
     let serialized = original.clone().serialize().unwrap();
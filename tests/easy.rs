@@ -0,0 +1,83 @@
+#![cfg(feature = "std")]
+
+use hostlink::protocol::{
+    responses::status::StatusMode, Address, Area, EasyCommand, MessageKind, NodeId, ProtocolError,
+    TimerInstruction,
+};
+
+fn params(command: EasyCommand) -> (MessageKind, String) {
+    let message = command.into_message(NodeId::new(0).unwrap());
+
+    (
+        message.kind(),
+        String::from_utf8(message.params().to_vec()).unwrap(),
+    )
+}
+
+#[test]
+fn easy_area_commands() {
+    assert_eq!(
+        params(EasyCommand::make_area_read(Area::Tc, 12, 3).unwrap()),
+        (MessageKind::PvRead, "00120003".into())
+    );
+    assert_eq!(
+        params(EasyCommand::make_area_write(Area::Hr, 5, &[0x1234, 0xABCD]).unwrap()),
+        (MessageKind::HrAreaWrite, "00051234ABCD".into())
+    );
+    assert_eq!(
+        params(EasyCommand::make_tc_status_write(7, &[true, false, true]).unwrap()),
+        (MessageKind::TcStatusWrite, "0007101".into())
+    );
+    assert_eq!(
+        params(EasyCommand::make_status_write(StatusMode::Monitor)),
+        (MessageKind::StatusWrite, "02".into())
+    );
+}
+
+#[test]
+fn easy_sv_and_forced() {
+    assert_eq!(
+        params(EasyCommand::make_sv_read(TimerInstruction::Tim, 1, None).unwrap()),
+        (MessageKind::SvRead1, "TIM 0001".into())
+    );
+    assert_eq!(
+        params(EasyCommand::make_sv_change(TimerInstruction::Cntr, 20, Some(300), 150).unwrap()),
+        (MessageKind::SvChange2, "0300CNTR00200150".into())
+    );
+    assert_eq!(
+        params(EasyCommand::make_forced("IR0010.05".parse().unwrap(), false).unwrap()),
+        (MessageKind::ForcedReset, "CIO 001005".into())
+    );
+    assert_eq!(
+        params(EasyCommand::make_error_read(true)),
+        (MessageKind::ErrorRead, "01".into())
+    );
+    assert_eq!(
+        params(EasyCommand::make_abort()),
+        (MessageKind::Abort, String::new())
+    );
+}
+
+#[test]
+fn easy_validation() {
+    assert_eq!(
+        EasyCommand::make_area_read(Area::Dm, 0, 31),
+        Err(ProtocolError::InvalidCount(31))
+    );
+    assert_eq!(
+        EasyCommand::make_area_write(Area::Dm, 0, &[]),
+        Err(ProtocolError::InvalidCount(0))
+    );
+    assert!(matches!(
+        EasyCommand::make_area_read(Area::Dm, 9999, 2),
+        Err(ProtocolError::InvalidAddress(_))
+    ));
+    assert_eq!(
+        EasyCommand::make_sv_change(TimerInstruction::Tim, 1, None, 10_000),
+        Err(ProtocolError::InvalidValue(10_000))
+    );
+    assert!(matches!(
+        EasyCommand::make_forced(Address::word(Area::Dm, 10), true),
+        Err(ProtocolError::InvalidAddress(_))
+    ));
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, DeviceError, Error, PlcDevice},
+    protocol::{Area, NodeId, ProtocolError},
+    sim::{Fault, FaultInjector, SimulatedPlc},
+};
+use std::{io::ErrorKind, time::Duration};
+
+fn connect(
+    faults: impl FnOnce(FaultInjector<SimulatedPlc>) -> FaultInjector<SimulatedPlc>,
+) -> PlcDevice {
+    let node = NodeId::new(3).unwrap();
+    let responder = faults(FaultInjector::new(SimulatedPlc::new(node)));
+    let transport = MemoryTransport::new(responder).with_timeout(Duration::from_millis(20));
+
+    PlcDevice::with_transport(Box::new(transport), node)
+}
+
+#[test]
+fn fault_corrupt_fcs() {
+    let mut device = connect(|faults| faults.on_request(0, Fault::CorruptFcs));
+
+    assert!(matches!(
+        device.status(),
+        Err(Error::Protocol(ProtocolError::FcsMismatch { .. }))
+    ));
+    assert!(device.status().is_ok());
+
+    // `@03RD000069` has the FCS `5A`, which is still a mismatch and not a broken frame
+    let node = NodeId::new(3).unwrap();
+    let mut plc = SimulatedPlc::new(node);
+    plc.write(Area::Dm, 0, &[0x69]).unwrap();
+    let transport = MemoryTransport::new(FaultInjector::new(plc).on_request(0, Fault::CorruptFcs));
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+
+    assert!(matches!(
+        device.read_words(Area::Dm, 0, 1),
+        Err(Error::Protocol(ProtocolError::FcsMismatch { .. }))
+    ));
+}
+
+#[test]
+fn fault_timeouts() {
+    let mut device = connect(|faults| {
+        faults
+            .on_request(0, Fault::NoReply)
+            .on_request(1, Fault::Truncate(6))
+            .on_request(2, Fault::NoTerminator)
+            .on_request(3, Fault::Delay(Duration::from_millis(50)))
+    });
+
+    for _ in 0..4 {
+        assert!(
+            matches!(device.test(), Err(Error::Io(error)) if error.kind() == ErrorKind::TimedOut)
+        );
+    }
+}
+
+#[test]
+fn fault_wrong_node() {
+    let mut device =
+        connect(|faults| faults.on_request(0, Fault::WrongNode(NodeId::new(4).unwrap())));
+
+    assert!(matches!(
+        device.status(),
+        Err(Error::UnexpectedResponse(node, _)) if *node == 4
+    ));
+}
+
+#[test]
+fn fault_end_code() {
+    let mut device = connect(|faults| {
+        faults
+            .on_request(0, Fault::EndCode(DeviceError::FCSError))
+            .on_request(1, Fault::EndCode(DeviceError::IllegalFrameLength))
+    });
+
+    assert!(matches!(
+        device.status(),
+        Err(Error::Device(DeviceError::FCSError))
+    ));
+    assert!(matches!(
+        device.status(),
+        Err(Error::Device(DeviceError::IllegalFrameLength))
+    ));
+    assert!(device.status().is_ok());
+}
+
+#[test]
+fn fault_retries() {
+    let mut device = connect(|faults| {
+        faults
+            .on_request(0, Fault::CorruptFcs)
+            .on_request(1, Fault::EndCode(DeviceError::FCSError))
+            .on_request(2, Fault::Delay(Duration::from_millis(5)))
+            .on_request(3, Fault::EndCode(DeviceError::AddressOver))
+    });
+    device.set_retries(2);
+
+    // two failed attempts, then a slow but valid response
+    assert!(device.status().is_ok());
+
+    // not a link error, so it won't be retried
+    assert!(matches!(
+        device.status(),
+        Err(Error::Device(DeviceError::AddressOver))
+    ));
+}
+
+#[test]
+fn fault_late_reply_dropped() {
+    let mut device = connect(|faults| {
+        let mut faults = faults.on_request(0, Fault::Delay(Duration::from_millis(50)));
+        faults.inner_mut().write(Area::Dm, 0, &[1, 2]).unwrap();
+        faults
+    });
+    device.set_retries(1);
+
+    // the retry is answered by its own reply, not the late one
+    assert_eq!(device.read_words(Area::Dm, 0, 1).unwrap(), [1]);
+    assert_eq!(device.read_words(Area::Dm, 1, 1).unwrap(), [2]);
+}
+
+#[test]
+fn fault_retryable_errors() {
+    assert!(Error::Protocol(ProtocolError::MissingFcs).is_retryable());
+    assert!(Error::Protocol(ProtocolError::Device(DeviceError::FCSError)).is_retryable());
+
+    // valid frames, or commands rejected before they are sent
+    assert!(!Error::Protocol(ProtocolError::Device(DeviceError::AddressOver)).is_retryable());
+    assert!(!Error::Protocol(ProtocolError::InvalidTestData).is_retryable());
+    assert!(!Error::Protocol(ProtocolError::UnknownErrorCode('Z', 'Z')).is_retryable());
+}
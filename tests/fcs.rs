@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use hostlink::protocol::{Message, MessageKind, MessageParams, NodeId};
 
 #[test]
@@ -0,0 +1,422 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, DeviceError, Error, PlcDevice},
+    fins::{
+        Clock, EndCode, FinsAddress, FinsArea, FinsClient, FinsCommand, FinsHeader, FinsTcpClient,
+        FinsUdpClient, RunMode,
+    },
+    protocol::{MessageKind, NodeId, ProtocolError, Request},
+    server::{Handler, HostlinkServer},
+};
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, UdpSocket},
+    time::Duration,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A CJ2M CPU unit answering FINS commands, with a few words of DM.
+#[derive(Debug, Default)]
+struct Cj2m {
+    dm: [u16; 16],
+    running: bool,
+    /// Service ID to answer with instead of the command's
+    sid: Option<u8>,
+}
+
+impl Cj2m {
+    fn execute(&mut self, command: &[u8]) -> (u16, Vec<u8>) {
+        match command {
+            [0x01, 0x01, 0x82, address @ .., 0, count_high, count_low] => {
+                let start = usize::from(u16::from_be_bytes([address[0], address[1]]));
+                let count = usize::from(u16::from_be_bytes([*count_high, *count_low]));
+
+                match self.dm.get(start..start + count) {
+                    Some(words) => (
+                        0,
+                        words.iter().flat_map(|word| word.to_be_bytes()).collect(),
+                    ),
+                    None => (0x1103, Vec::new()),
+                }
+            }
+            [0x01, 0x02, 0x82, address_high, address_low, 0, _, _, data @ ..] => {
+                if self.running {
+                    return (0x2108, Vec::new());
+                }
+
+                let start = usize::from(u16::from_be_bytes([*address_high, *address_low]));
+                for (index, word) in data.chunks(2).enumerate() {
+                    self.dm[start + index] = u16::from_be_bytes([word[0], word[1]]);
+                }
+
+                (0, Vec::new())
+            }
+            [0x04, 0x01, 0xFF, 0xFF, 0x04] => {
+                self.running = true;
+                (0, Vec::new())
+            }
+            [0x04, 0x02, 0xFF, 0xFF] => {
+                self.running = false;
+                (0, Vec::new())
+            }
+            [0x05, 0x01, 0x00] => {
+                let mut data = format!("{:<20}{:<20}", "CJ2M-CPU31", "02.01").into_bytes();
+                data.extend_from_slice(&[0; 40]);
+                // a non-fatal CPU unit error is flagged alongside normal completion
+                (0x0040, data)
+            }
+            [0x07, 0x01] => (0, vec![0x24, 0x05, 0x31, 0x23, 0x59, 0x07, 0x05]),
+            _ => (0x0401, Vec::new()),
+        }
+    }
+
+    /// Answers a FINS frame as sent over Ethernet (10 byte header, command code and parameters).
+    fn respond(&mut self, frame: &[u8]) -> Vec<u8> {
+        let (header, command) = frame.split_at(10);
+        let (end_code, data) = self.execute(command);
+
+        // the response goes back to the source, with the ICF's response bit set
+        let mut response = vec![
+            0xC0, 0, 0x02, header[6], header[7], header[8], header[3], header[4], header[5],
+        ];
+        response.push(self.sid.unwrap_or(header[9]));
+        response.extend_from_slice(&command[..2]);
+        response.extend_from_slice(&end_code.to_be_bytes());
+        response.extend(data);
+        response
+    }
+}
+
+impl Handler for Cj2m {
+    fn other(&mut self, request: &Request) -> Result<String, DeviceError> {
+        if request.kind() != MessageKind::Fins {
+            return Err(DeviceError::InstructionNotFound);
+        }
+
+        let digits = String::from_utf8_lossy(&request.params()[1..]);
+        let bytes: Vec<u8> = (0..digits.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&digits[index..index + 2], 16))
+            .collect::<Result<_, _>>()
+            .map_err(|_| DeviceError::EntryNumberData)?;
+        let [_, da2, sa2, sid, command @ ..] = &bytes[..] else {
+            return Err(DeviceError::FormatError);
+        };
+
+        let (end_code, data) = self.execute(command);
+        let mut response = vec![0x40, *sa2, *da2, self.sid.unwrap_or(*sid)];
+        response.extend_from_slice(&command[..2]);
+        response.extend_from_slice(&end_code.to_be_bytes());
+        response.extend(data);
+
+        Ok(response.iter().map(|byte| format!("{byte:02X}")).collect())
+    }
+}
+
+fn device(plc: Cj2m) -> PlcDevice {
+    let node = NodeId::new(0).unwrap();
+    let server = HostlinkServer::new(node, plc);
+
+    PlcDevice::with_transport(Box::new(MemoryTransport::new(server)), node)
+}
+
+#[test]
+fn fins_hostlink_encapsulation() {
+    let command = FinsCommand::MemoryAreaRead {
+        area: FinsArea::Dm,
+        address: 100,
+        count: 2,
+    };
+    let request = command
+        .to_hostlink(NodeId::new(5).unwrap(), FinsHeader::new(0x1A))
+        .unwrap();
+    let params = String::from_utf8_lossy(request.params());
+
+    assert_eq!(request.kind(), MessageKind::Fins);
+    assert_eq!(params, concat!("0", "0000001A", "0101", "82006400", "0002"));
+    assert!(request.serialize().unwrap().starts_with("@05FA0"));
+}
+
+#[test]
+fn fins_commands() {
+    let mut device = device(Cj2m::default());
+
+    let data = FinsCommand::MemoryAreaWrite {
+        area: FinsArea::Dm,
+        address: 2,
+        data: vec![0x1234, 0xABCD],
+    };
+    device.fins(&data).unwrap();
+
+    let read = FinsCommand::MemoryAreaRead {
+        area: FinsArea::Dm,
+        address: 1,
+        count: 3,
+    };
+    assert_eq!(
+        device.fins(&read).unwrap().words().unwrap(),
+        vec![0, 0x1234, 0xABCD]
+    );
+
+    let clock = device
+        .fins(&FinsCommand::ClockRead)
+        .unwrap()
+        .clock()
+        .unwrap();
+    assert_eq!(
+        clock,
+        Clock {
+            year: 2024,
+            month: 5,
+            day: 31,
+            hour: 23,
+            minute: 59,
+            second: 7,
+            day_of_week: 5,
+        }
+    );
+    assert_eq!(clock.to_string(), "2024-05-31 23:59:07");
+
+    let controller = device.fins(&FinsCommand::ControllerDataRead).unwrap();
+    assert!(!controller.end_code.is_fatal_error());
+    let controller = controller.controller_data().unwrap();
+    assert_eq!(controller.model, "CJ2M-CPU31");
+    assert_eq!(controller.version, "02.01");
+
+    device.fins(&FinsCommand::Run(RunMode::Run)).unwrap();
+    assert!(matches!(
+        device.fins(&data),
+        Err(Error::Fins(EndCode(0x2108)))
+    ));
+    device.fins(&FinsCommand::Stop).unwrap();
+    device.fins(&data).unwrap();
+}
+
+#[test]
+fn fins_errors() {
+    let mut device = device(Cj2m {
+        sid: Some(0x77),
+        ..Cj2m::default()
+    });
+
+    // the response carries a different service ID
+    assert!(matches!(
+        device.fins(&FinsCommand::ClockRead),
+        Err(Error::UnexpectedResponse(_, MessageKind::Fins))
+    ));
+
+    // PLCs without FINS support answer with an end code
+    let mut device = device_without_fins();
+    assert!(matches!(
+        device.fins(&FinsCommand::ClockRead),
+        Err(Error::Device(DeviceError::InstructionNotFound))
+    ));
+
+    let error = Error::Fins(EndCode(0x1103));
+    assert_eq!(error.to_string(), "FINS end code 1103 (parameter error)");
+}
+
+#[test]
+fn fins_word_limits() {
+    let node = NodeId::new(0).unwrap();
+    let header = FinsHeader::new(1);
+    let read = |count| FinsCommand::MemoryAreaRead {
+        area: FinsArea::Dm,
+        address: 0,
+        count,
+    };
+    let write = |count| FinsCommand::MemoryAreaWrite {
+        area: FinsArea::Dm,
+        address: 0,
+        data: vec![0; count],
+    };
+
+    // the response to a read and a write command have to fit into a single Host Link frame
+    assert!(read(26).to_hostlink(node, header).is_ok());
+    assert_eq!(
+        read(27).to_hostlink(node, header),
+        Err(ProtocolError::InvalidCount(27))
+    );
+    assert!(write(24).to_hostlink(node, header).is_ok());
+    assert_eq!(
+        write(25).to_hostlink(node, header),
+        Err(ProtocolError::InvalidCount(25))
+    );
+    assert_eq!(
+        read(0).to_hostlink(node, header),
+        Err(ProtocolError::InvalidCount(0))
+    );
+
+    // over Ethernet, frames are limited to 2012 bytes
+    assert!(read(999).to_frame(header).is_ok());
+    assert_eq!(
+        read(1000).to_frame(header),
+        Err(ProtocolError::InvalidCount(1000))
+    );
+    assert!(write(997).to_frame(header).is_ok());
+    assert_eq!(
+        write(70_000).to_frame(header),
+        Err(ProtocolError::InvalidCount(70_000))
+    );
+
+    // nothing is sent
+    let mut device = device(Cj2m::default());
+    assert!(matches!(
+        device.read_memory(FinsArea::Dm, 0, 27),
+        Err(Error::Protocol(ProtocolError::InvalidCount(27)))
+    ));
+    assert_eq!(device.stats().snapshot().total().requests, 0);
+}
+
+/// Exercises a PLC through the common client trait, no matter how it's attached.
+fn exercise(client: &mut dyn FinsClient) {
+    client.write_memory(FinsArea::Dm, 4, &[7, 8, 9]).unwrap();
+    assert_eq!(
+        client.read_memory(FinsArea::Dm, 3, 4).unwrap(),
+        vec![0, 7, 8, 9]
+    );
+    assert_eq!(client.read_clock().unwrap().year, 2024);
+    assert_eq!(client.read_controller_data().unwrap().model, "CJ2M-CPU31");
+
+    client.run(RunMode::Run).unwrap();
+    assert!(matches!(
+        client.write_memory(FinsArea::Dm, 0, &[1]),
+        Err(Error::Fins(EndCode(0x2108)))
+    ));
+    client.stop().unwrap();
+}
+
+#[test]
+fn fins_udp() {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let address = socket.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let mut plc = Cj2m::default();
+        let mut buffer = [0; 2048];
+
+        // garbage and a stale response to an earlier command arrive first, and must be skipped
+        let (count, peer) = socket.recv_from(&mut buffer).unwrap();
+        socket.send_to(b"\xC0\0", peer).unwrap();
+        let mut stale = plc.respond(&buffer[..count]);
+        stale[9] = stale[9].wrapping_sub(1);
+        socket.send_to(&stale, peer).unwrap();
+        socket
+            .send_to(&plc.respond(&buffer[..count]), peer)
+            .unwrap();
+
+        while let Ok((count, peer)) = socket.recv_from(&mut buffer) {
+            // node addresses derived from 127.0.0.1
+            assert_eq!(buffer[4], 1);
+            assert_eq!(buffer[7], 1);
+
+            let response = plc.respond(&buffer[..count]);
+            socket.send_to(&response, peer).unwrap();
+
+            if buffer[10..12] == [0x04, 0x02] {
+                break;
+            }
+        }
+    });
+
+    let mut client = FinsUdpClient::connect(address, TIMEOUT).unwrap();
+    assert_eq!(
+        client.destination(),
+        FinsAddress {
+            network: 0,
+            node: 1,
+            unit: 0
+        }
+    );
+
+    exercise(&mut client);
+    server.join().unwrap();
+
+    // attached over Host Link, the same code works as well
+    exercise(&mut device(Cj2m::default()));
+}
+
+#[test]
+fn fins_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut plc = Cj2m::default();
+
+        let receive = |stream: &mut std::net::TcpStream| {
+            let mut header = [0; 16];
+            stream.read_exact(&mut header).ok()?;
+            assert_eq!(&header[..4], b"FINS");
+
+            let length = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+            let mut data = vec![0; length - 8];
+            stream.read_exact(&mut data).unwrap();
+
+            Some((header[11], data))
+        };
+        let send = |stream: &mut std::net::TcpStream, command: u8, data: &[u8]| {
+            #[allow(clippy::cast_possible_truncation)]
+            let length = (8 + data.len()) as u32;
+            let mut frame = b"FINS".to_vec();
+            frame.extend_from_slice(&length.to_be_bytes());
+            frame.extend_from_slice(&[0, 0, 0, command, 0, 0, 0, 0]);
+            frame.extend_from_slice(data);
+            stream.write_all(&frame).unwrap();
+        };
+
+        // node address handshake: the client asks for automatic assignment
+        assert_eq!(receive(&mut stream), Some((0, vec![0, 0, 0, 0])));
+        send(&mut stream, 1, &[0, 0, 0, 0xEF, 0, 0, 0, 0x0A]);
+
+        while let Some((command, frame)) = receive(&mut stream) {
+            assert_eq!(command, 2);
+            assert_eq!((frame[4], frame[7]), (0x0A, 0xEF));
+            send(&mut stream, 2, &plc.respond(&frame));
+        }
+    });
+
+    let mut client = FinsTcpClient::connect(address, TIMEOUT).unwrap();
+    assert_eq!(client.source().node, 0xEF);
+    assert_eq!(client.destination().node, 0x0A);
+
+    exercise(&mut client);
+    drop(client);
+    server.join().unwrap();
+}
+
+#[test]
+fn fins_tcp_length_limit() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 20];
+        stream.read_exact(&mut request).unwrap();
+
+        // a node address response claiming almost 4 GiB of data
+        let mut frame = b"FINS".to_vec();
+        frame.extend_from_slice(&u32::MAX.to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0]);
+        stream.write_all(&frame).unwrap();
+    });
+
+    let error = FinsTcpClient::connect(address, TIMEOUT).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    server.join().unwrap();
+}
+
+fn device_without_fins() -> PlcDevice {
+    struct C200h;
+    impl Handler for C200h {}
+
+    let node = NodeId::new(0).unwrap();
+    PlcDevice::with_transport(
+        Box::new(MemoryTransport::new(HostlinkServer::new(node, C200h))),
+        node,
+    )
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, PlcDevice},
+    modbus::{Exception, Map, ModbusGateway, Table},
+    protocol::{responses::status::StatusMode, Address, Area, NodeId},
+    sim::SimulatedPlc,
+};
+use std::{
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+};
+
+fn gateway() -> (Arc<Mutex<SimulatedPlc>>, ModbusGateway) {
+    let node = NodeId::new(0).unwrap();
+    let plc = Arc::new(Mutex::new(SimulatedPlc::new(node)));
+    let device = PlcDevice::with_transport(Box::new(MemoryTransport::new(plc.clone())), node);
+    let map = Map::new()
+        .holding_registers(0, 100, Address::word(Area::Dm, 1000))
+        .input_registers(0, 10, Address::word(Area::Hr, 95))
+        .coils(0, 64, Address::bit(Area::IrSr, 10, 8).unwrap());
+
+    (plc, ModbusGateway::new(device, map))
+}
+
+#[test]
+fn modbus_registers() {
+    let (plc, gateway) = gateway();
+    plc.lock()
+        .unwrap()
+        .write(Area::Dm, 1001, &[0x1234, 0xABCD])
+        .unwrap();
+
+    assert_eq!(
+        gateway.handle_pdu(&[0x03, 0, 1, 0, 2]),
+        [0x03, 4, 0x12, 0x34, 0xAB, 0xCD]
+    );
+
+    // write multiple registers
+    assert_eq!(
+        gateway.handle_pdu(&[0x10, 0, 50, 0, 2, 4, 0, 1, 0, 2]),
+        [0x10, 0, 50, 0, 2]
+    );
+    assert_eq!(plc.lock().unwrap().read(Area::Dm, 1050, 2).unwrap(), [1, 2]);
+
+    // write single register
+    assert_eq!(
+        gateway.handle_pdu(&[0x06, 0, 99, 0, 7]),
+        [0x06, 0, 99, 0, 7]
+    );
+    assert_eq!(plc.lock().unwrap().read(Area::Dm, 1099, 1).unwrap(), [7]);
+
+    // unmapped, or partially mapped
+    assert_eq!(
+        gateway.handle_pdu(&[0x03, 0, 99, 0, 2]),
+        [0x83, Exception::IllegalDataAddress as u8]
+    );
+
+    // mapped, but beyond the end of HR
+    assert_eq!(
+        gateway.handle_pdu(&[0x04, 0, 0, 0, 10]),
+        [0x84, Exception::IllegalDataAddress as u8]
+    );
+
+    assert_eq!(
+        gateway.handle_pdu(&[0x2B, 0x0E]),
+        [0xAB, Exception::IllegalFunction as u8]
+    );
+}
+
+#[test]
+fn modbus_coils() {
+    let (plc, gateway) = gateway();
+    plc.lock()
+        .unwrap()
+        .write(Area::IrSr, 10, &[0x0300, 0x0001])
+        .unwrap();
+
+    // coils 0..10 are IR010.08..IR011.01
+    assert_eq!(
+        gateway.handle_pdu(&[0x01, 0, 0, 0, 10]),
+        [0x01, 2, 0x03, 0x01]
+    );
+
+    assert_eq!(
+        gateway.handle_pdu(&[0x05, 0, 2, 0xFF, 0x00]),
+        [0x05, 0, 2, 0xFF, 0x00]
+    );
+    assert_eq!(
+        gateway.handle_pdu(&[0x0F, 0, 8, 0, 2, 1, 0b10]),
+        [0x0F, 0, 8, 0, 2]
+    );
+    assert_eq!(
+        plc.lock().unwrap().read(Area::IrSr, 10, 2).unwrap(),
+        [0x0700, 0x0002]
+    );
+
+    assert_eq!(
+        gateway.handle_pdu(&[0x05, 0, 2, 0x12, 0x34]),
+        [0x85, Exception::IllegalDataValue as u8]
+    );
+
+    // writes aren't allowed in RUN mode
+    plc.lock().unwrap().set_mode(StatusMode::Run);
+    assert_eq!(
+        gateway.handle_pdu(&[0x05, 0, 2, 0, 0]),
+        [0x85, Exception::ServerDeviceBusy as u8]
+    );
+}
+
+#[test]
+fn modbus_word_overflow() {
+    let map = Map::new()
+        .holding_registers(0, 10, Address::word(Area::Dm, 65530))
+        .coils(0, 160, Address::bit(Area::IrSr, 65534, 8).unwrap());
+
+    assert_eq!(
+        map.resolve(Table::HoldingRegisters, 5, 1),
+        Some(Address::word(Area::Dm, 65535))
+    );
+    assert_eq!(map.resolve(Table::HoldingRegisters, 6, 1), None);
+    assert_eq!(map.resolve(Table::HoldingRegisters, 4, 3), None);
+
+    assert_eq!(
+        map.resolve(Table::Coils, 23, 1),
+        Some(Address::bit(Area::IrSr, 65535, 15).unwrap())
+    );
+    assert_eq!(map.resolve(Table::Coils, 24, 1), None);
+    assert_eq!(map.resolve(Table::Coils, 20, 8), None);
+
+    let node = NodeId::new(0).unwrap();
+    let plc = Arc::new(Mutex::new(SimulatedPlc::new(node)));
+    let device = PlcDevice::with_transport(Box::new(MemoryTransport::new(plc)), node);
+    let gateway = ModbusGateway::new(device, map);
+
+    assert_eq!(
+        gateway.handle_pdu(&[0x03, 0, 8, 0, 1]),
+        [0x83, Exception::IllegalDataAddress as u8]
+    );
+    assert_eq!(
+        gateway.handle_pdu(&[0x05, 0, 100, 0xFF, 0]),
+        [0x85, Exception::IllegalDataAddress as u8]
+    );
+}
+
+#[test]
+fn modbus_tcp() {
+    let (plc, gateway) = gateway();
+    plc.lock().unwrap().write(Area::Dm, 1000, &[42]).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        gateway.serve_connection(stream).unwrap();
+    });
+
+    let mut client = TcpStream::connect(address).unwrap();
+    client
+        .write_all(&[
+            0x00, 0x07, 0x00, 0x00, 0x00, 0x06, 0x01, 0x03, 0x00, 0x00, 0x00, 0x01,
+        ])
+        .unwrap();
+
+    let mut response = [0; 11];
+    client.read_exact(&mut response).unwrap();
+    assert_eq!(
+        response,
+        [0x00, 0x07, 0x00, 0x00, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00, 42]
+    );
+
+    drop(client);
+    server.join().unwrap();
+}
@@ -0,0 +1,88 @@
+//! Only uses what is available without `std`, so that it also runs with `--no-default-features`.
+
+use hostlink::protocol::{
+    DeviceError, FrameDecoder, Message, MessageKind, MessageParams, MessageRef, NodeId,
+    ProtocolError, Response, MAX_PARAMS_LENGTH,
+};
+
+#[test]
+fn core_header_codes() {
+    for kind in MessageKind::ALL {
+        let code = kind.code().as_bytes();
+
+        assert_eq!(MessageKind::from_code([code[0], code[1]]), Some(kind));
+    }
+
+    assert_eq!(MessageKind::from_code(*b"ZZ"), None);
+}
+
+#[test]
+fn core_decode_errors() {
+    assert_eq!(
+        MessageRef::decode(b"@0xRD00*\r"),
+        Err(ProtocolError::InvalidNodeId('0', 'x'))
+    );
+    assert_eq!(
+        MessageRef::decode(b"@05ZZ00*\r"),
+        Err(ProtocolError::UnknownHeaderCode('Z', 'Z'))
+    );
+}
+
+#[test]
+fn core_params() {
+    let mut params = MessageParams::from_slice(b"0012").unwrap();
+    params.extend_from_slice(b"0ABC").unwrap();
+
+    assert_eq!(&*params, b"00120ABC");
+
+    let node = NodeId::new(5).unwrap();
+    let response = Response::new(node, MessageKind::DmAreaRead, DeviceError::None, params);
+    let message = Message::try_from(response.clone()).unwrap();
+
+    assert_eq!(&**message.params(), b"0000120ABC");
+    assert_eq!(Response::try_from(message), Ok(response));
+}
+
+/// The same conversions are fallible with and without `std`, so this compiles either way.
+#[test]
+fn core_same_api_with_std() {
+    fn command(params: &str) -> Result<Message, ProtocolError> {
+        let node = NodeId::new(1).unwrap();
+
+        Ok(Message::new(node, MessageKind::Test, params.try_into()?))
+    }
+
+    assert!(command("ABC").is_ok());
+
+    let long = [b'0'; MAX_PARAMS_LENGTH + 1];
+    assert_eq!(
+        MessageParams::try_from(&long[..]),
+        Err(ProtocolError::BufferTooSmall(MAX_PARAMS_LENGTH + 1))
+    );
+    assert!(matches!(
+        "ZZ".parse::<MessageKind>(),
+        Err(ProtocolError::UnknownCommand(code)) if code == "ZZ"
+    ));
+}
+
+#[test]
+fn core_codec_and_decoder() {
+    let node = NodeId::new(7).unwrap();
+    let command = MessageRef::new(node, MessageKind::DmAreaRead, b"01000002");
+
+    let mut frame = [0; 32];
+    let length = command.encode(&mut frame).unwrap();
+    assert_eq!(length, command.encoded_len());
+
+    // noise, then the frame in two chunks
+    let mut decoder = FrameDecoder::new();
+    decoder.push(b"xx");
+    decoder.push(&frame[..5]);
+    assert!(matches!(decoder.next_frame(), Some(Err(..))));
+    assert_eq!(decoder.next_frame(), None);
+
+    decoder.push(&frame[5..length]);
+    let decoded = decoder.next_message().unwrap().unwrap();
+    assert_eq!(decoded, command);
+    assert!(decoder.pending().is_empty());
+}
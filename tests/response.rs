@@ -0,0 +1,96 @@
+#![cfg(feature = "std")]
+
+use hostlink::device::DeviceError;
+use hostlink::protocol::{
+    responses::{
+        status::{Status, StatusMemory, StatusMode},
+        words::Words,
+    },
+    Message, MessageKind, NodeId, ProtocolError, Request, Response, ResponseData,
+};
+
+fn frame(node: u8, kind: MessageKind, params: &str) -> Box<str> {
+    Message::new(NodeId::new(node).unwrap(), kind, params.try_into().unwrap())
+        .serialize()
+        .unwrap()
+}
+
+#[test]
+fn response_separates_end_code() {
+    let response = Response::parse(&frame(3, MessageKind::DmAreaRead, "0012340ABC")).unwrap();
+
+    assert_eq!(response.node(), NodeId::new(3).unwrap());
+    assert_eq!(response.end_code(), DeviceError::None);
+    assert_eq!(&**response.data(), b"12340ABC");
+    assert_eq!(response.error(), None);
+
+    // the test command's response echoes the block of data without an end code
+    let echo = Response::parse(&frame(3, MessageKind::Test, "15ABC")).unwrap();
+
+    assert_eq!(echo.end_code(), DeviceError::None);
+    assert_eq!(echo.decode().unwrap(), ResponseData::Echo("15ABC".into()));
+
+    // a request is taken as-is
+    let request = Request::parse(&frame(3, MessageKind::DmAreaRead, "01000002")).unwrap();
+
+    assert_eq!(&**request.params(), b"01000002");
+}
+
+#[test]
+fn response_decode() {
+    let decode = |kind, params| Response::parse(&frame(0, kind, params)).unwrap().decode();
+
+    assert_eq!(
+        decode(MessageKind::DmAreaRead, "0012340ABC").unwrap(),
+        ResponseData::Words(Words(heapless::Vec::from_slice(&[0x1234, 0x0ABC]).unwrap()))
+    );
+    assert_eq!(
+        decode(MessageKind::TcStatusRead, "00101").unwrap(),
+        ResponseData::Flags(vec![true, false, true])
+    );
+    assert_eq!(
+        decode(MessageKind::DmAreaWrite, "00").unwrap(),
+        ResponseData::Empty
+    );
+    assert!(matches!(
+        decode(MessageKind::StatusRead, "000210").unwrap(),
+        ResponseData::Status(..)
+    ));
+    // the status bytes are sent as hexadecimal digits: FALS, error, RUN; no write protection
+    assert_eq!(
+        decode(MessageKind::StatusRead, "009208").unwrap(),
+        ResponseData::Status(Status {
+            fals: true,
+            error: true,
+            mode: StatusMode::Run,
+            memory: StatusMemory {
+                size: None,
+                write_protection: false,
+            },
+        })
+    );
+    assert!(decode(MessageKind::StatusRead, "00G210").is_err());
+    assert!(decode(MessageKind::DmAreaRead, "0012G4").is_err());
+}
+
+#[test]
+fn response_errors() {
+    let response = Response::parse(&frame(1, MessageKind::DmAreaWrite, "15")).unwrap();
+
+    assert_eq!(response.end_code(), DeviceError::EntryNumberData);
+    assert_eq!(response.error(), Some(DeviceError::EntryNumberData));
+    assert!(matches!(
+        response.decode(),
+        Err(ProtocolError::Device(DeviceError::EntryNumberData))
+    ));
+
+    // serializing restores the end code in front of the data
+    let original = frame(1, MessageKind::DmAreaRead, "13");
+    let response = Response::parse(&original).unwrap();
+
+    assert_eq!(response.serialize().unwrap(), original);
+    assert!(matches!(
+        Response::parse(&frame(1, MessageKind::DmAreaRead, "0")),
+        Err(ProtocolError::ErrorCodeBadLength)
+    ));
+}
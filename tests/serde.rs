@@ -0,0 +1,127 @@
+#![cfg(feature = "serde")]
+
+use hostlink::protocol::{
+    responses::status::{Status, StatusMemory, StatusMode},
+    DeviceError, Message, MessageKind, MessageParams, NodeId,
+};
+use serde_json::json;
+
+#[test]
+fn serde_message() {
+    let message = Message::new(
+        NodeId::new(5).unwrap(),
+        MessageKind::DmAreaRead,
+        MessageParams::from_slice(b"01000002").unwrap(),
+    );
+    let value = serde_json::to_value(&message).unwrap();
+
+    assert_eq!(
+        value,
+        json!({"node": 5, "kind": "RD", "params": "01000002"})
+    );
+    assert_eq!(serde_json::from_value::<Message>(value).unwrap(), message);
+
+    // params that aren't valid UTF-8 are bytes, which JSON writes as an array
+    let binary = Message::new(
+        NodeId::new(5).unwrap(),
+        MessageKind::Test,
+        MessageParams::from_slice(&[b'A', 0xFF]).unwrap(),
+    );
+    let value = serde_json::to_value(&binary).unwrap();
+
+    assert_eq!(value["params"], json!([65, 255]));
+    assert_eq!(serde_json::from_value::<Message>(value).unwrap(), binary);
+    assert!(
+        serde_json::from_value::<Message>(json!({"node": 5, "kind": "TS", "params": [256]}))
+            .is_err()
+    );
+
+    assert!(serde_json::from_value::<NodeId>(json!(100)).is_err());
+    assert!(serde_json::from_value::<MessageKind>(json!("ZZ")).is_err());
+}
+
+#[test]
+fn serde_device_error() {
+    let value = serde_json::to_value(DeviceError::FCSError).unwrap();
+
+    assert_eq!(value, json!({"code": "13", "description": "FCS error"}));
+    assert_eq!(
+        serde_json::from_value::<DeviceError>(value).unwrap(),
+        DeviceError::FCSError
+    );
+    assert_eq!(
+        serde_json::from_value::<DeviceError>(json!({"code": "A8"})).unwrap(),
+        DeviceError::IllegalFrameLength
+    );
+    assert!(serde_json::from_value::<DeviceError>(json!({"code": "ZZ"})).is_err());
+    // two bytes, but a single character
+    assert!(serde_json::from_value::<DeviceError>(json!({"code": "é"})).is_err());
+
+    let unsupported = DeviceError::UnsupportedCommand(MessageKind::PcModelRead);
+    let value = serde_json::to_value(unsupported).unwrap();
+    assert_eq!(value["command"], "MM");
+    assert_eq!(
+        serde_json::from_value::<DeviceError>(value).unwrap(),
+        unsupported
+    );
+    assert!(serde_json::from_value::<DeviceError>(json!({"code": "IC"})).is_err());
+}
+
+#[test]
+fn serde_status() {
+    let status = Status {
+        fals: false,
+        error: true,
+        mode: StatusMode::Monitor,
+        memory: StatusMemory {
+            size: Some(4096),
+            write_protection: true,
+        },
+    };
+    let value = serde_json::to_value(status).unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "fals": false,
+            "error": true,
+            "mode": "Monitor",
+            "memory": {"size": 4096, "write_protection": true},
+        })
+    );
+    assert_eq!(serde_json::from_value::<Status>(value).unwrap(), status);
+}
+
+/// Formats which don't write field names or types need the same fields in the same order.
+#[test]
+fn serde_binary_round_trip() {
+    fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+        bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap()
+    }
+
+    for error in [
+        DeviceError::None,
+        DeviceError::AddressOver,
+        DeviceError::UnsupportedCommand(MessageKind::StatusRead),
+    ] {
+        assert_eq!(round_trip(&error), error);
+    }
+
+    let message = Message::new(
+        NodeId::new(5).unwrap(),
+        MessageKind::Test,
+        MessageParams::from_slice(&[b'A', 0xFF]).unwrap(),
+    );
+    assert_eq!(round_trip(&message), message);
+
+    let status = Status {
+        fals: true,
+        error: false,
+        mode: StatusMode::Run,
+        memory: StatusMemory {
+            size: None,
+            write_protection: false,
+        },
+    };
+    assert_eq!(round_trip(&status), status);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use hostlink::protocol::{fcs::fcs, Message, MessageKind, MessageParams, NodeId};
 
 #[test]
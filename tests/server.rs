@@ -0,0 +1,130 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, DeviceError, Error, PlcDevice},
+    protocol::{
+        responses::status::{Status, StatusMemory, StatusMode},
+        Area, MessageKind, NodeId, Request,
+    },
+    server::{Handler, HostlinkServer},
+};
+use std::net::{TcpListener, TcpStream};
+
+/// A tank whose level is derived from the inlet valve position.
+#[derive(Debug, Default)]
+struct Tank {
+    valve: u16,
+}
+
+impl Handler for Tank {
+    fn status_read(&mut self) -> Result<Status, DeviceError> {
+        Ok(Status {
+            fals: false,
+            error: false,
+            mode: StatusMode::Monitor,
+            memory: StatusMemory {
+                size: None,
+                write_protection: true,
+            },
+        })
+    }
+
+    fn area_read(&mut self, area: Area, start: u16, count: u16) -> Result<Vec<u16>, DeviceError> {
+        match (area, start, count) {
+            (Area::Dm, 0, 2) => Ok(vec![self.valve, self.valve * 10]),
+            _ => Err(DeviceError::AddressOver),
+        }
+    }
+
+    fn area_write(&mut self, area: Area, start: u16, data: &[u16]) -> Result<(), DeviceError> {
+        match (area, start, data) {
+            (Area::Dm, 0, [valve]) => {
+                self.valve = *valve;
+                Ok(())
+            }
+            _ => Err(DeviceError::AddressOver),
+        }
+    }
+}
+
+#[test]
+fn server_handler() {
+    let node = NodeId::new(12).unwrap();
+    let server = HostlinkServer::new(node, Tank { valve: 4 });
+    let mut device = PlcDevice::with_transport(Box::new(MemoryTransport::new(server)), node);
+
+    device.test().unwrap();
+    assert_eq!(device.status().unwrap().mode, StatusMode::Monitor);
+    assert_eq!(device.read_words(Area::Dm, 0, 2).unwrap(), vec![4, 40]);
+    assert!(matches!(
+        device.read_words(Area::Hr, 0, 1),
+        Err(Error::Device(DeviceError::AddressOver))
+    ));
+}
+
+#[test]
+fn server_unsupported() {
+    struct Echo;
+    impl Handler for Echo {}
+
+    let node = NodeId::new(0).unwrap();
+    let server = HostlinkServer::new(node, Echo);
+    let mut device = PlcDevice::with_transport(Box::new(MemoryTransport::new(server)), node);
+
+    device.test().unwrap();
+    assert!(matches!(
+        device.status(),
+        Err(Error::Device(DeviceError::InstructionNotFound))
+    ));
+}
+
+#[test]
+fn server_format_error() {
+    let node = NodeId::new(12).unwrap();
+    let server = HostlinkServer::new(node, Tank::default());
+    let mut device = PlcDevice::with_transport(Box::new(MemoryTransport::new(server)), node);
+
+    let mut send = |kind, params: &str| {
+        device
+            .send_command(Request::new(node, kind, params.try_into().unwrap()))
+            .unwrap()
+            .error()
+    };
+
+    // signs and other non-digits are rejected like a PLC does, instead of being parsed
+    assert_eq!(
+        send(MessageKind::DmAreaRead, "+0000001"),
+        Some(DeviceError::FormatError)
+    );
+    assert_eq!(
+        send(MessageKind::DmAreaRead, "0000+001"),
+        Some(DeviceError::FormatError)
+    );
+    assert_eq!(
+        send(MessageKind::DmAreaWrite, "0000+123"),
+        Some(DeviceError::FormatError)
+    );
+    assert_eq!(send(MessageKind::DmAreaRead, "00000002"), None);
+}
+
+#[test]
+fn server_serve() {
+    let node = NodeId::new(1).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut server = HostlinkServer::new(node, Tank::default());
+        server.serve(stream).unwrap();
+
+        server.into_handler().valve
+    });
+
+    let stream = TcpStream::connect(address).unwrap();
+    let mut device = PlcDevice::with_transport(Box::new(stream), node);
+    device.test().unwrap();
+    drop(device);
+
+    assert_eq!(server.join().unwrap(), 0);
+}
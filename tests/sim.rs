@@ -0,0 +1,112 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, DeviceError, Error, PlcDevice, Quality, Value},
+    protocol::{responses::status::StatusMode, Area, NodeId},
+    sim::SimulatedPlc,
+};
+use std::sync::{Arc, Mutex};
+
+fn connect() -> (Arc<Mutex<SimulatedPlc>>, PlcDevice) {
+    let node = NodeId::new(1).unwrap();
+    let plc = Arc::new(Mutex::new(SimulatedPlc::new(node)));
+    let device = PlcDevice::with_transport(Box::new(MemoryTransport::new(plc.clone())), node);
+
+    (plc, device)
+}
+
+#[test]
+fn sim_test_and_status() {
+    let (plc, mut device) = connect();
+    plc.lock().unwrap().set_mode(StatusMode::Run);
+
+    device.test().unwrap();
+
+    let status = device.status().unwrap();
+    assert_eq!(status, plc.lock().unwrap().status());
+    assert_eq!(status.mode, StatusMode::Run);
+}
+
+#[test]
+fn sim_read_words() {
+    let (plc, mut device) = connect();
+    let data: Vec<u16> = (0..45).collect();
+    plc.lock().unwrap().write(Area::Dm, 1000, &data).unwrap();
+
+    assert_eq!(device.read_words(Area::Dm, 1000, 45).unwrap(), data);
+}
+
+#[test]
+fn sim_address_over() {
+    let (_, mut device) = connect();
+
+    assert!(matches!(
+        device.read_words(Area::Hr, 99, 2),
+        Err(Error::Device(DeviceError::AddressOver))
+    ));
+}
+
+#[test]
+fn sim_subscribe() {
+    let (plc, mut device) = connect();
+    let level = device.subscribe("DM0010".parse().unwrap(), 5);
+    let flag = device.subscribe("IR0001.03".parse().unwrap(), 0);
+
+    device.poll_subscriptions();
+    assert_eq!(level.try_recv().unwrap().value, Some(Value::Word(0)));
+    assert_eq!(flag.try_recv().unwrap().value, Some(Value::Bit(false)));
+
+    plc.lock().unwrap().write(Area::Dm, 10, &[5]).unwrap();
+    device.poll_subscriptions();
+    assert!(level.try_recv().is_err());
+
+    plc.lock().unwrap().write(Area::Dm, 10, &[6]).unwrap();
+    plc.lock().unwrap().write(Area::IrSr, 1, &[0b1000]).unwrap();
+    device.poll_subscriptions();
+
+    let change = level.try_recv().unwrap();
+    assert_eq!(change.previous, Some(Value::Word(0)));
+    assert_eq!(change.value, Some(Value::Word(6)));
+    assert_eq!(flag.try_recv().unwrap().value, Some(Value::Bit(true)));
+}
+
+#[test]
+fn sim_subscribe_quality() {
+    let (_, mut device) = connect();
+    let missing = device.subscribe("HR0200".parse().unwrap(), 0);
+
+    device.poll_subscriptions();
+    device.poll_subscriptions();
+
+    let change = missing.try_recv().unwrap();
+    assert_eq!(change.quality, Quality::Stale);
+    assert_eq!(change.value, None);
+    assert!(missing.try_recv().is_err());
+}
+
+#[test]
+fn sim_write_and_control() {
+    let (plc, mut device) = connect();
+    let data: Vec<u16> = (100..140).collect();
+
+    device.write_words(Area::Hr, 10, &data).unwrap();
+    assert_eq!(plc.lock().unwrap().read(Area::Hr, 10, 40).unwrap(), data);
+
+    device.force("LR0002.15".parse().unwrap(), true).unwrap();
+    assert_eq!(plc.lock().unwrap().read(Area::Lr, 2, 1).unwrap(), [0x8000]);
+    device.cancel_forced().unwrap();
+    assert!(plc.lock().unwrap().forced().is_empty());
+
+    assert_eq!(device.model().unwrap().0, SimulatedPlc::MODEL_C200H);
+
+    plc.lock().unwrap().set_errors([0x0001, 0x8000]);
+    assert_eq!(device.errors(true).unwrap(), vec![0x0001, 0x8000]);
+    assert_eq!(device.errors(false).unwrap(), vec![0, 0]);
+
+    device.set_mode(StatusMode::Run).unwrap();
+    assert_eq!(device.status().unwrap().mode, StatusMode::Run);
+    assert!(matches!(
+        device.write_words(Area::Dm, 0, &[1]),
+        Err(Error::Device(DeviceError::NotExecutableInRunMode))
+    ));
+}
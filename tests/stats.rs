@@ -0,0 +1,85 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, DeviceError, Histogram, LinkStats, PlcDevice},
+    protocol::{Area, MessageKind, NodeId},
+    sim::{Fault, FaultInjector, SimulatedPlc},
+};
+use std::{sync::Arc, time::Duration};
+
+#[test]
+fn stats_counters() {
+    let node = NodeId::new(2).unwrap();
+    let responder = FaultInjector::new(SimulatedPlc::new(node))
+        .on_request(0, Fault::CorruptFcs)
+        .on_request(1, Fault::NoReply)
+        .on_request(3, Fault::EndCode(DeviceError::AddressOver));
+    let transport = MemoryTransport::new(responder).with_timeout(Duration::from_millis(20));
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+    device.set_retries(2);
+
+    device.status().unwrap();
+    assert!(device.read_words(Area::Dm, 0, 1).is_err());
+
+    let snapshot = device.stats().snapshot();
+    let status = &snapshot.entries[&(node, MessageKind::StatusRead)];
+    assert_eq!(status.requests, 3);
+    assert_eq!(status.retries, 2);
+    assert_eq!(status.fcs_errors, 1);
+    assert_eq!(status.timeouts, 1);
+    assert_eq!(status.successes, 1);
+
+    let read = &snapshot.entries[&(node, MessageKind::DmAreaRead)];
+    assert_eq!(read.end_codes[&DeviceError::AddressOver], 1);
+    assert_eq!(read.retries, 0);
+
+    let total = snapshot.node(node);
+    assert_eq!(total.requests, 4);
+    assert_eq!(total.failures(), 3);
+    assert_eq!(total.latency.count(), 2);
+}
+
+#[test]
+fn stats_shared_between_devices() {
+    let stats = Arc::new(LinkStats::new());
+
+    for node in [0, 1] {
+        let node = NodeId::new(node).unwrap();
+        let mut device = PlcDevice::with_transport(
+            Box::new(MemoryTransport::new(SimulatedPlc::new(node))),
+            node,
+        );
+        device.set_stats(stats.clone());
+        device.test().unwrap();
+    }
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.entries.len(), 2);
+    assert_eq!(snapshot.total().successes, 2);
+
+    let metrics = snapshot.to_prometheus();
+    assert!(metrics.contains("hostlink_requests_total{node=\"01\",kind=\"TS\"} 1"));
+    assert!(metrics.contains("hostlink_latency_seconds_count{node=\"00\",kind=\"TS\"} 1"));
+    assert!(metrics.contains("# TYPE hostlink_latency_seconds histogram"));
+
+    stats.reset();
+    assert!(stats.snapshot().entries.is_empty());
+}
+
+#[test]
+fn stats_histogram_quantiles() {
+    let mut histogram = Histogram::default();
+    assert_eq!(histogram.p50(), None);
+
+    for _ in 0..98 {
+        histogram.observe(Duration::from_millis(8));
+    }
+    histogram.observe(Duration::from_millis(150));
+    histogram.observe(Duration::from_millis(150));
+
+    assert_eq!(histogram.p50(), Some(Duration::from_millis(10)));
+    assert_eq!(histogram.p99(), Some(Duration::from_millis(200)));
+
+    histogram.observe(Duration::from_secs(10));
+    assert_eq!(histogram.quantile(1.0), None);
+}
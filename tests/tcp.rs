@@ -0,0 +1,155 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{Parity, PlcDevice, Rfc2217Transport, SerialSettings, TcpTransport},
+    protocol::{Area, NodeId},
+    server::HostlinkServer,
+    sim::SimulatedPlc,
+};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Reads a single frame (up to and including `\r`) from a raw connection.
+fn read_frame(stream: &mut BufReader<TcpStream>) -> String {
+    let mut frame = Vec::new();
+    stream.read_until(b'\r', &mut frame).unwrap();
+    String::from_utf8(frame).unwrap()
+}
+
+#[test]
+fn tcp_transport() {
+    let node = NodeId::new(3).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let mut plc = SimulatedPlc::new(node);
+        plc.write(Area::Dm, 10, &[1234]).unwrap();
+        let mut server = HostlinkServer::new(node, plc);
+
+        for _ in 0..2 {
+            let (stream, _) = listener.accept().unwrap();
+            server.serve(stream).unwrap();
+        }
+    });
+
+    let mut transport = TcpTransport::connect(address, TIMEOUT).unwrap();
+    transport.set_nodelay(true).unwrap();
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+
+    device.test().unwrap();
+    assert_eq!(device.read_words(Area::Dm, 10, 1).unwrap(), vec![1234]);
+
+    drop(device);
+    let transport = TcpTransport::connect(address, TIMEOUT).unwrap();
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+    device.write_words(Area::Dm, 11, &[5678]).unwrap();
+    assert_eq!(
+        device.read_words(Area::Dm, 10, 2).unwrap(),
+        vec![1234, 5678]
+    );
+
+    drop(device);
+    server.join().unwrap();
+}
+
+#[test]
+fn tcp_transport_reconnect() {
+    let node = NodeId::new(0).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let mut server = HostlinkServer::new(node, SimulatedPlc::new(node));
+
+        // the first connection answers a single command and is then closed by the server
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let response = server.handle_frame(&read_frame(&mut reader)).unwrap();
+        reader.get_mut().write_all(response.as_bytes()).unwrap();
+        drop(reader);
+
+        let (stream, _) = listener.accept().unwrap();
+        server.serve(stream).unwrap();
+    });
+
+    let transport = TcpTransport::connect(address, TIMEOUT).unwrap();
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+    device.set_retries(1);
+
+    device.test().unwrap();
+    // the first attempt finds the connection closed, the retry reconnects
+    device.write_words(Area::Dm, 0, &[42]).unwrap();
+    assert_eq!(device.read_words(Area::Dm, 0, 1).unwrap(), vec![42]);
+
+    let snapshot = device.stats().snapshot();
+    assert_eq!(snapshot.total().retries, 1);
+
+    drop(device);
+    server.join().unwrap();
+}
+
+#[test]
+fn rfc2217_transport() {
+    const IAC: u8 = 255;
+    let node = NodeId::new(0).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+        // WILL COM-PORT-OPTION, WILL BINARY, DO BINARY, DO SUPPRESS-GO-AHEAD
+        let mut offer = [0; 12];
+        stream.read_exact(&mut offer).unwrap();
+        assert_eq!(offer[..3], [IAC, 251, 44]);
+
+        // ask for echo, which must be refused, and accept the option
+        stream.write_all(&[IAC, 253, 1, IAC, 253, 44]).unwrap();
+
+        let mut received = Vec::new();
+        while !received.ends_with(b"\r") {
+            let mut buf = [0; 256];
+            let count = stream.read(&mut buf).unwrap();
+            assert_ne!(count, 0);
+            received.extend_from_slice(&buf[..count]);
+        }
+
+        let contains = |bytes: &[u8]| received.windows(bytes.len()).any(|window| window == bytes);
+        // WONT ECHO, SET-BAUDRATE 19200, SET-PARITY odd
+        assert!(contains(&[IAC, 252, 1]));
+        assert!(contains(&[IAC, 250, 44, 1, 0, 0, 0x4B, 0, IAC, 240]));
+        assert!(contains(&[IAC, 250, 44, 3, 2, IAC, 240]));
+
+        let start = received.iter().rposition(|byte| *byte == b'@').unwrap();
+        let command = std::str::from_utf8(&received[start..]).unwrap();
+        let mut server = HostlinkServer::new(node, SimulatedPlc::new(node));
+        let reply = server.handle_frame(command).unwrap().into_bytes();
+
+        // the response is interrupted by a NOP and a baud rate acknowledgement
+        let mut response = reply[..5].to_vec();
+        response.extend_from_slice(&[IAC, 241]);
+        response.extend_from_slice(&[IAC, 250, 44, 101, 0, 0, 0x4B, 0, IAC, 240]);
+        response.extend_from_slice(&reply[5..]);
+        stream.write_all(&response).unwrap();
+    });
+
+    let settings = SerialSettings {
+        baud: 19200,
+        parity: Parity::Odd,
+        ..SerialSettings::default()
+    };
+    let transport = Rfc2217Transport::connect(address, settings, TIMEOUT).unwrap();
+    assert_eq!(transport.settings(), settings);
+
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+    device.test().unwrap();
+
+    server.join().unwrap();
+}
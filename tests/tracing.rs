@@ -0,0 +1,89 @@
+#![cfg(feature = "tracing")]
+
+use hostlink::{
+    device::{transport::MemoryTransport, PlcDevice},
+    protocol::NodeId,
+    sim::{Fault, FaultInjector, SimulatedPlc},
+};
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Level, Metadata, Subscriber,
+};
+
+/// Collects the level and fields of every event.
+#[derive(Default)]
+struct Collector {
+    events: Arc<Mutex<Vec<(Level, String)>>>,
+    next_span: AtomicU64,
+}
+
+struct Fields(String);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push_str(&format!("{}={value:?} ", field.name()));
+    }
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields(String::new());
+        event.record(&mut fields);
+
+        self.events
+            .lock()
+            .unwrap()
+            .push((*event.metadata().level(), fields.0));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn tracing_transactions() {
+    let collector = Collector::default();
+    let events = collector.events.clone();
+
+    let node = NodeId::new(1).unwrap();
+    let responder = FaultInjector::new(SimulatedPlc::new(node)).on_request(0, Fault::CorruptFcs);
+    let transport = MemoryTransport::new(responder).with_timeout(Duration::from_millis(20));
+    let mut device = PlcDevice::with_transport(Box::new(transport), node);
+    device.set_retries(1);
+
+    tracing::subscriber::with_default(collector, || device.status().unwrap());
+
+    let events = events.lock().unwrap();
+    let find = |level: Level, text: &str| {
+        events
+            .iter()
+            .any(|(event_level, fields)| *event_level == level && fields.contains(text))
+    };
+
+    assert!(find(Level::TRACE, "frame=@01MS5F*\\r"));
+    assert!(find(Level::DEBUG, "response could not be parsed"));
+    assert!(find(Level::DEBUG, "attempt=1"));
+    assert!(find(Level::DEBUG, "end_code=00"));
+}
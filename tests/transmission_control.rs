@@ -0,0 +1,105 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    device::{
+        transport::{MemoryTransport, Reply, Responder},
+        Error, PlcDevice,
+    },
+    protocol::{
+        fcs::fcs, Area, EasyCommand, Message, MessageKind, MessageRef, NodeId, ProtocolError,
+    },
+};
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+/// Records every frame and answers the first one with `reply`.
+#[derive(Default)]
+struct Recorder {
+    frames: Vec<Vec<u8>>,
+    reply: Vec<u8>,
+}
+
+impl Responder for Recorder {
+    fn respond(&mut self, frame: &[u8]) -> Reply {
+        self.frames.push(frame.to_vec());
+
+        std::mem::take(&mut self.reply).into()
+    }
+}
+
+fn connect(reply: Vec<u8>) -> (Arc<Mutex<Recorder>>, PlcDevice) {
+    let node = NodeId::new(1).unwrap();
+    let recorder = Arc::new(Mutex::new(Recorder {
+        frames: Vec::new(),
+        reply,
+    }));
+    let mut device =
+        PlcDevice::with_transport(Box::new(MemoryTransport::new(recorder.clone())), node);
+    device.set_retries(0);
+
+    (recorder, device)
+}
+
+#[test]
+fn transmission_control_frames() {
+    let node = NodeId::new(5).unwrap();
+    let abort = EasyCommand::make_abort().into_message(node);
+    let initialize = EasyCommand::make_initialize().into_message(node);
+
+    assert_eq!(
+        &*abort.serialize().unwrap(),
+        format!("@05XZ{}*\r", fcs("@05XZ").unwrap())
+    );
+    assert_eq!(&*initialize.serialize().unwrap(), "@**\r");
+    assert_eq!(
+        Message::parse("@**\r").unwrap(),
+        Message::new_with_empty_params(NodeId::default(), MessageKind::Initialize)
+    );
+    assert_eq!(
+        MessageRef::decode(b"@**00*\r"),
+        Err(ProtocolError::MissingTerminator)
+    );
+
+    assert_eq!(MessageKind::from_str("XZ"), Ok(MessageKind::Abort));
+    assert_eq!(MessageKind::from_str("**"), Ok(MessageKind::Initialize));
+    assert!(!MessageKind::Abort.has_response());
+}
+
+#[test]
+fn transmission_control_abort_multi_frame() {
+    // an intermediate frame ends with the FCS and a carriage return, without `*`
+    let frame = format!("@01RD000001{}\r", fcs("@01RD000001").unwrap());
+    let (recorder, mut device) = connect(frame.into_bytes());
+
+    assert!(matches!(
+        device.read_words(Area::Dm, 0, 1),
+        Err(Error::MultiFrameResponse(..))
+    ));
+
+    let frames = &recorder.lock().unwrap().frames;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(
+        MessageRef::decode(&frames[1]).unwrap().kind(),
+        MessageKind::Abort
+    );
+}
+
+#[test]
+fn transmission_control_initialize_is_explicit() {
+    // a cut-off frame is an ordinary link error, which mustn't reset the other units on the line
+    let (recorder, mut device) = connect(b"@01RD0000".to_vec());
+
+    assert!(matches!(
+        device.read_words(Area::Dm, 0, 1),
+        Err(Error::Io(..))
+    ));
+    assert_eq!(recorder.lock().unwrap().frames.len(), 1);
+
+    device.initialize().unwrap();
+
+    let frames = &recorder.lock().unwrap().frames;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[1], b"@**\r");
+}
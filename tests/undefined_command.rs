@@ -0,0 +1,94 @@
+#![cfg(feature = "std")]
+
+use hostlink::{
+    analyzer::{Analyzer, Direction},
+    device::{transport::MemoryTransport, DeviceError, Error, PlcDevice},
+    protocol::{fcs::fcs, Area, MessageKind, MessageParams, NodeId, Response},
+    server::{Handler, HostlinkServer},
+};
+use std::time::SystemTime;
+
+/// An older model which doesn't know the PC MODEL READ command.
+struct OldModel;
+
+impl Handler for OldModel {
+    fn model_read(&mut self) -> Result<u8, DeviceError> {
+        Err(DeviceError::UnsupportedCommand(MessageKind::PcModelRead))
+    }
+
+    fn area_read(&mut self, _area: Area, _start: u16, count: u16) -> Result<Vec<u16>, DeviceError> {
+        Ok(vec![0; usize::from(count)])
+    }
+}
+
+fn undefined_command_frame(node: &str) -> String {
+    let frame = format!("@{node}IC");
+    let fcs = fcs(&frame).unwrap();
+
+    format!("{frame}{fcs}*\r")
+}
+
+#[test]
+fn undefined_command_response() {
+    let node = NodeId::new(1).unwrap();
+    let frame = undefined_command_frame("01");
+
+    let response = Response::parse(&frame).unwrap();
+    assert_eq!(response.kind(), MessageKind::UndefinedCommand);
+    assert_eq!(
+        response.error(),
+        Some(DeviceError::UnsupportedCommand(
+            MessageKind::UndefinedCommand
+        ))
+    );
+
+    let response = Response::new(
+        node,
+        MessageKind::PcModelRead,
+        DeviceError::UnsupportedCommand(MessageKind::PcModelRead),
+        MessageParams::default(),
+    );
+    assert_eq!(&*response.serialize().unwrap(), frame);
+    assert!(!DeviceError::UnsupportedCommand(MessageKind::PcModelRead).is_transmission_error());
+}
+
+#[test]
+fn undefined_command_device() {
+    let node = NodeId::new(3).unwrap();
+    let server = HostlinkServer::new(node, OldModel);
+    let mut device = PlcDevice::with_transport(Box::new(MemoryTransport::new(server)), node);
+
+    let error = device.model().unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Device(DeviceError::UnsupportedCommand(MessageKind::PcModelRead))
+    ));
+    assert!(!error.is_retryable());
+
+    // the link is still usable afterwards
+    assert_eq!(device.read_words(Area::Dm, 0, 2).unwrap(), vec![0, 0]);
+}
+
+#[test]
+fn undefined_command_unknown_header_code() {
+    let mut server = HostlinkServer::new(NodeId::new(2).unwrap(), OldModel);
+    let unknown = format!("@02ZZ{}*\r", fcs("@02ZZ").unwrap());
+    assert_eq!(
+        server.handle_frame(&unknown),
+        Some(undefined_command_frame("02"))
+    );
+
+    // a command known to the host but not to the PLC, as seen on the line
+    let command = format!("@02MM{}*\r", fcs("@02MM").unwrap());
+    let response = server.handle_frame(&command).unwrap();
+    assert_eq!(response, undefined_command_frame("02"));
+
+    let mut analyzer = Analyzer::new();
+    let stream = [command, response].concat();
+    let frames = analyzer.feed(stream.as_bytes(), SystemTime::now());
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[1].direction, Direction::Response);
+    assert!(frames[1].issues.is_empty());
+    assert_eq!(frames[1].describe(), "undefined command");
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "std")]
+
+use hostlink::protocol::{
+    responses::words::{Words, WordsParseError},
+    Message, MessageKind, NodeId, ProtocolError,
+};
+
+#[test]
+fn words_1() {
+    let node = NodeId::new(0).unwrap();
+    let response = Message::new(
+        node,
+        MessageKind::DmAreaRead,
+        "000001ABCD".try_into().unwrap(),
+    );
+
+    assert_eq!(
+        Words::try_from(response),
+        Ok(Words(heapless::Vec::from_slice(&[0x0001, 0xABCD]).unwrap()))
+    );
+}
+
+#[test]
+fn words_2() {
+    let node = NodeId::new(0).unwrap();
+    let response = Message::new(node, MessageKind::DmAreaRead, "00012".try_into().unwrap());
+
+    assert_eq!(
+        Words::try_from(response),
+        Err(WordsParseError::BadLength(3))
+    );
+    assert_eq!(
+        ProtocolError::from(WordsParseError::BadLength(3)).to_string(),
+        "Failed to parse words: Data length must be a multiple of 4, got 3"
+    );
+}
+
+#[test]
+fn words_encode() {
+    assert_eq!(Words::encode(&[0x1234, 0x00ff]), "123400FF");
+}